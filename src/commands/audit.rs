@@ -0,0 +1,123 @@
+use crate::commands::sync::{workspace_repos, SyncError};
+use crate::git::{self, UnpushedBranch};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditOptions {
+    pub config_path: PathBuf,
+    /// Same filter as `sync`: group name or part of a repo URL
+    pub target: Option<String>,
+}
+
+/// Work in one repo that exists only on this machine
+#[derive(Debug, Clone, Default)]
+pub struct RepoAudit {
+    pub name: String,
+    pub local_path: PathBuf,
+    pub unpushed_branches: Vec<UnpushedBranch>,
+    pub stashes: Vec<String>,
+    /// Untracked files not excluded by `.gitignore`
+    pub untracked_files: Vec<String>,
+    /// Tracked files with uncommitted changes
+    pub uncommitted_files: Vec<String>,
+}
+
+impl RepoAudit {
+    pub fn is_clean(&self) -> bool {
+        self.unpushed_branches.is_empty()
+            && self.stashes.is_empty()
+            && self.untracked_files.is_empty()
+            && self.uncommitted_files.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AuditReport {
+    pub repos_checked: usize,
+    /// Repos with local-only work
+    pub repos: Vec<RepoAudit>,
+}
+
+impl AuditReport {
+    pub fn has_findings(&self) -> bool {
+        !self.repos.is_empty()
+    }
+}
+
+/// Find work that would be lost with the machine: unpushed commits, stashes,
+/// untracked and uncommitted files. Remotes are compared as last fetched.
+pub fn audit_command(options: &AuditOptions) -> Result<AuditReport, AuditError> {
+    let repos = workspace_repos(&options.config_path, &options.target)?;
+    let mut report = AuditReport::default();
+
+    // Mirrors hold nothing that isn't on the remote
+    for repo in repos
+        .iter()
+        .filter(|repo| repo.mode.is_checkout() && repo.is_cloned())
+    {
+        report.repos_checked += 1;
+
+        let path = repo.local_path.as_path();
+        let audit = RepoAudit {
+            name: repo.name.clone(),
+            local_path: repo.local_path.clone(),
+            unpushed_branches: git::unpushed_branches(path),
+            stashes: git::stashes(path),
+            untracked_files: git::untracked_files(path),
+            uncommitted_files: git::uncommitted_files(path),
+        };
+
+        if !audit.is_clean() {
+            report.repos.push(audit);
+        }
+    }
+
+    print_audit(&report);
+    Ok(report)
+}
+
+fn print_audit(report: &AuditReport) {
+    println!("\n=== Unpushed Work ===");
+
+    for repo in &report.repos {
+        println!("\n{} ({})", repo.name, repo.local_path.display());
+
+        for unpushed in &repo.unpushed_branches {
+            let unit = if unpushed.commits == 1 {
+                "commit"
+            } else {
+                "commits"
+            };
+            println!(
+                "  Branch {}: {} {} not on any remote",
+                unpushed.branch, unpushed.commits, unit
+            );
+        }
+        for stash in &repo.stashes {
+            println!("  Stash {}", stash);
+        }
+        for file in &repo.uncommitted_files {
+            println!("  Uncommitted: {}", file);
+        }
+        for file in &repo.untracked_files {
+            println!("  Untracked: {}", file);
+        }
+    }
+
+    if report.has_findings() {
+        println!(
+            "\n{} of {} repositories have unpushed work",
+            report.repos.len(),
+            report.repos_checked
+        );
+    } else {
+        println!("Nothing unpushed in {} repositories", report.repos_checked);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::commands::sync::{workspace_repos, SyncError, WorkspaceRepo};
+use crate::git;
+use crate::redact::redact;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BranchError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+}
+
+#[derive(Debug, Clone)]
+pub struct BranchOptions {
+    pub config_path: PathBuf,
+    /// Same filter as `sync`: group name or part of a repo URL
+    pub target: Option<String>,
+    pub branch: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckoutOptions {
+    pub config_path: PathBuf,
+    pub target: Option<String>,
+    pub branch: String,
+    /// Checked out in repos that don't have `branch`
+    pub fallback: Option<String>,
+}
+
+/// Outcome of a branch operation in one repo
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct RepoBranchResult {
+    pub name: String,
+    pub local_path: PathBuf,
+    /// What was done, e.g. `created from origin/main`
+    pub message: String,
+    /// `None` if the operation succeeded
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BranchReport {
+    pub results: Vec<RepoBranchResult>,
+}
+
+impl BranchReport {
+    pub fn failures(&self) -> usize {
+        self.results.iter().filter(|r| r.error.is_some()).count()
+    }
+}
+
+/// Where a branch exists in one repo
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct BranchPresence {
+    pub name: String,
+    pub local_path: PathBuf,
+    pub local: bool,
+    /// As `origin/<branch>`, as of the last fetch
+    pub remote: bool,
+    pub checked_out: bool,
+    /// Set when the repo couldn't be inspected
+    pub error: Option<String>,
+}
+
+/// Create `branch` from the default branch in every selected repo and check it out
+pub fn branch_create_command(options: &BranchOptions) -> Result<BranchReport, BranchError> {
+    let repos = checkouts(&options.config_path, &options.target)?;
+
+    let results = repos
+        .iter()
+        .map(|repo| run_in_repo(repo, |path| create_branch(repo, path, &options.branch)))
+        .collect();
+
+    let report = BranchReport { results };
+    print_results(&format!("Create branch {}", options.branch), &report);
+    Ok(report)
+}
+
+/// Check out `branch` (or the fallback) in every selected repo
+pub fn checkout_command(options: &CheckoutOptions) -> Result<BranchReport, BranchError> {
+    let repos = checkouts(&options.config_path, &options.target)?;
+
+    let results = repos
+        .iter()
+        .map(|repo| {
+            run_in_repo(repo, |path| match checkout_branch(path, &options.branch)? {
+                Some(message) => Ok(message),
+                None => match options.fallback {
+                    Some(ref fallback) => checkout_branch(path, fallback)?
+                        .map(|message| format!("{} not found; {}", options.branch, message))
+                        .ok_or_else(|| {
+                            format!("neither {} nor {} found", options.branch, fallback)
+                        }),
+                    None => Err(format!("{} not found", options.branch)),
+                },
+            })
+        })
+        .collect();
+
+    let report = BranchReport { results };
+    print_results(&format!("Check out {}", options.branch), &report);
+    Ok(report)
+}
+
+/// Which selected repos have `branch`, locally or on `origin`
+pub fn branch_list_command(options: &BranchOptions) -> Result<Vec<BranchPresence>, BranchError> {
+    let repos = checkouts(&options.config_path, &options.target)?;
+
+    let presence: Vec<BranchPresence> = repos
+        .iter()
+        .map(|repo| {
+            let path = repo.local_path.as_path();
+            let cloned = repo.is_cloned();
+            BranchPresence {
+                name: repo.name.clone(),
+                local_path: repo.local_path.clone(),
+                local: cloned && git::has_ref(path, &format!("refs/heads/{}", options.branch)),
+                remote: cloned
+                    && git::has_ref(path, &format!("refs/remotes/origin/{}", options.branch)),
+                checked_out: cloned
+                    && git::current_branch(path).as_deref() == Some(options.branch.as_str()),
+                error: if cloned {
+                    None
+                } else {
+                    Some("not cloned".to_string())
+                },
+            }
+        })
+        .collect();
+
+    print_presence(&options.branch, &presence);
+    Ok(presence)
+}
+
+/// Selected repos with a working tree; mirrors have no branches to work on
+fn checkouts(
+    config_path: &Path,
+    target: &Option<String>,
+) -> Result<Vec<WorkspaceRepo>, BranchError> {
+    let repos = workspace_repos(config_path, target)?;
+    Ok(repos
+        .into_iter()
+        .filter(|repo| repo.mode.is_checkout())
+        .collect())
+}
+
+fn run_in_repo(
+    repo: &WorkspaceRepo,
+    operation: impl FnOnce(&Path) -> Result<String, String>,
+) -> RepoBranchResult {
+    let outcome = if repo.is_cloned() {
+        operation(&repo.local_path)
+    } else {
+        Err("not cloned".to_string())
+    };
+
+    let (message, error) = match outcome {
+        Ok(message) => (message, None),
+        Err(error) => (String::new(), Some(redact(&error))),
+    };
+
+    RepoBranchResult {
+        name: repo.name.clone(),
+        local_path: repo.local_path.clone(),
+        message,
+        error,
+    }
+}
+
+fn create_branch(repo: &WorkspaceRepo, path: &Path, branch: &str) -> Result<String, String> {
+    if git::has_ref(path, &format!("refs/heads/{}", branch)) {
+        return Err(format!("{} already exists", branch));
+    }
+
+    let default = git::origin_head(path)
+        .or_else(|| repo.default_branch.clone())
+        .ok_or_else(|| "default branch unknown; run sync first".to_string())?;
+    let start = format!("origin/{}", default);
+    if !git::has_ref(path, &format!("refs/remotes/{}", start)) {
+        return Err(format!("{} not fetched; run sync first", start));
+    }
+
+    git::run(
+        path,
+        &["checkout", "--quiet", "--no-track", "-b", branch, &start],
+    )?;
+    Ok(format!("created from {}", start))
+}
+
+/// Check out a local branch, or create one tracking `origin/<branch>`.
+/// Returns `None` if neither exists.
+fn checkout_branch(path: &Path, branch: &str) -> Result<Option<String>, String> {
+    if git::current_branch(path).as_deref() == Some(branch) {
+        return Ok(Some(format!("already on {}", branch)));
+    }
+
+    if git::has_ref(path, &format!("refs/heads/{}", branch)) {
+        git::run(path, &["checkout", "--quiet", branch])?;
+        return Ok(Some(format!("checked out {}", branch)));
+    }
+
+    let remote = format!("origin/{}", branch);
+    if git::has_ref(path, &format!("refs/remotes/{}", remote)) {
+        git::run(
+            path,
+            &["checkout", "--quiet", "--track", "-b", branch, &remote],
+        )?;
+        return Ok(Some(format!("checked out {} tracking {}", branch, remote)));
+    }
+
+    Ok(None)
+}
+
+fn print_results(title: &str, report: &BranchReport) {
+    println!("\n=== {} ===", title);
+
+    for result in &report.results {
+        match result.error {
+            None => println!("✓ {}: {}", result.name, result.message),
+            Some(ref error) => eprintln!("✗ {}: {}", result.name, error),
+        }
+    }
+
+    println!(
+        "\n{} of {} repositories succeeded",
+        report.results.len() - report.failures(),
+        report.results.len()
+    );
+}
+
+fn print_presence(branch: &str, presence: &[BranchPresence]) {
+    println!("\n=== Branch {} ===", branch);
+
+    for repo in presence {
+        if let Some(ref error) = repo.error {
+            println!("  {}: {}", repo.name, error);
+            continue;
+        }
+
+        let mut places = Vec::new();
+        if repo.local {
+            places.push(if repo.checked_out {
+                "local (checked out)"
+            } else {
+                "local"
+            });
+        }
+        if repo.remote {
+            places.push("origin");
+        }
+        if places.is_empty() {
+            places.push("-");
+        }
+        println!("  {}: {}", repo.name, places.join(", "));
+    }
+
+    let found = presence.iter().filter(|r| r.local || r.remote).count();
+    println!("\nFound in {} of {} repositories", found, presence.len());
+}
@@ -0,0 +1,269 @@
+use crate::commands::sync::{workspace_repos, SyncError, WorkspaceRepo};
+use crate::git;
+use crate::redact::redact;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BranchesError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+}
+
+/// What to do with the branches found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeleteMode {
+    /// Only report them
+    #[default]
+    Report,
+    /// Ask on the terminal before deleting each one
+    Ask,
+    /// Delete all of them without asking
+    Yes,
+}
+
+#[derive(Debug, Clone)]
+pub struct BranchesOptions {
+    pub config_path: PathBuf,
+    /// Same filter as `sync`: group name or part of a repo URL
+    pub target: Option<String>,
+    /// Find branches merged into the default branch or whose upstream is gone
+    pub merged: bool,
+    /// Find branches whose last commit is at least this many days old
+    pub stale_days: Option<u64>,
+    pub delete: DeleteMode,
+}
+
+/// Why a branch is up for cleanup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupReason {
+    /// Contained in the remote default branch
+    Merged,
+    /// Its upstream branch was deleted on the remote
+    UpstreamGone,
+    /// No commits for `stale_days`
+    Stale,
+}
+
+impl CleanupReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CleanupReason::Merged => "merged",
+            CleanupReason::UpstreamGone => "upstream gone",
+            CleanupReason::Stale => "stale",
+        }
+    }
+}
+
+/// A local branch found by the cleanup
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct BranchCandidate {
+    pub repo: String,
+    pub local_path: PathBuf,
+    pub branch: String,
+    pub reasons: Vec<CleanupReason>,
+    /// Days since the last commit
+    pub age_days: u64,
+    /// Why the branch must not be deleted, if it must not
+    pub protected: Option<String>,
+    pub deleted: bool,
+    /// Set when deleting failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BranchesReport {
+    pub candidates: Vec<BranchCandidate>,
+}
+
+impl BranchesReport {
+    pub fn deleted(&self) -> usize {
+        self.candidates.iter().filter(|c| c.deleted).count()
+    }
+
+    pub fn errors(&self) -> usize {
+        self.candidates.iter().filter(|c| c.error.is_some()).count()
+    }
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Find merged, orphaned or stale local branches in every selected repo and
+/// optionally delete them. Checked out branches and branches with commits
+/// not on any remote are never deleted.
+pub fn branches_command(options: &BranchesOptions) -> Result<BranchesReport, BranchesError> {
+    let repos = workspace_repos(&options.config_path, &options.target)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+
+    let mut report = BranchesReport::default();
+    for repo in repos
+        .iter()
+        .filter(|repo| repo.mode.is_checkout() && repo.is_cloned())
+    {
+        report
+            .candidates
+            .extend(find_candidates(repo, options, now));
+    }
+
+    print_candidates(options, &report);
+
+    if options.delete != DeleteMode::Report {
+        delete_candidates(&mut report, options.delete);
+    }
+
+    Ok(report)
+}
+
+fn find_candidates(
+    repo: &WorkspaceRepo,
+    options: &BranchesOptions,
+    now: u64,
+) -> Vec<BranchCandidate> {
+    let path = repo.local_path.as_path();
+    let default = git::origin_head(path).or_else(|| repo.default_branch.clone());
+
+    // Without a known default branch, nothing can be called merged
+    let merged = match (options.merged, default.as_deref()) {
+        (true, Some(default)) => {
+            git::merged_branches(path, &format!("refs/remotes/origin/{}", default))
+        }
+        _ => Vec::new(),
+    };
+
+    git::local_branches(path)
+        .into_iter()
+        .filter(|branch| Some(branch.name.as_str()) != default.as_deref())
+        .filter_map(|branch| {
+            let age_days = now.saturating_sub(branch.commit_time) / SECONDS_PER_DAY;
+
+            let mut reasons = Vec::new();
+            if merged.contains(&branch.name) {
+                reasons.push(CleanupReason::Merged);
+            }
+            if options.merged && branch.upstream_gone {
+                reasons.push(CleanupReason::UpstreamGone);
+            }
+            if options.stale_days.is_some_and(|days| age_days >= days) {
+                reasons.push(CleanupReason::Stale);
+            }
+            if reasons.is_empty() {
+                return None;
+            }
+
+            Some(BranchCandidate {
+                repo: repo.name.clone(),
+                local_path: repo.local_path.clone(),
+                protected: protection(path, &branch),
+                branch: branch.name,
+                reasons,
+                age_days,
+                deleted: false,
+                error: None,
+            })
+        })
+        .collect()
+}
+
+fn protection(repo: &Path, branch: &git::LocalBranch) -> Option<String> {
+    if branch.checked_out {
+        return Some("checked out".to_string());
+    }
+
+    match git::unpushed_commits(repo, &branch.name) {
+        Some(0) => None,
+        Some(1) => Some("1 unpushed commit".to_string()),
+        Some(count) => Some(format!("{} unpushed commits", count)),
+        None => Some("could not check for unpushed commits".to_string()),
+    }
+}
+
+fn delete_candidates(report: &mut BranchesReport, mode: DeleteMode) {
+    let stdin = std::io::stdin();
+    let mut answers = stdin.lock().lines();
+
+    for candidate in report
+        .candidates
+        .iter_mut()
+        .filter(|c| c.protected.is_none())
+    {
+        if mode == DeleteMode::Ask {
+            print!("Delete {} in {}? [y/N] ", candidate.branch, candidate.repo);
+            let _ = std::io::stdout().flush();
+
+            let answer = answers
+                .next()
+                .and_then(|line| line.ok())
+                .unwrap_or_default();
+            if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                continue;
+            }
+        }
+
+        match git::run(&candidate.local_path, &["branch", "-D", &candidate.branch]) {
+            Ok(_) => {
+                candidate.deleted = true;
+                println!("✓ Deleted {} in {}", candidate.branch, candidate.repo);
+            }
+            Err(error) => {
+                let error = redact(&error);
+                eprintln!(
+                    "✗ Failed to delete {} in {}: {}",
+                    candidate.branch, candidate.repo, error
+                );
+                candidate.error = Some(error);
+            }
+        }
+    }
+
+    println!("\nDeleted {} branches", report.deleted());
+}
+
+fn print_candidates(options: &BranchesOptions, report: &BranchesReport) {
+    let mut criteria = Vec::new();
+    if options.merged {
+        criteria.push("merged or upstream gone".to_string());
+    }
+    if let Some(days) = options.stale_days {
+        criteria.push(format!("no commits for {} days", days));
+    }
+    println!("\n=== Branches: {} ===", criteria.join(", "));
+
+    let mut current_repo = None;
+    for candidate in &report.candidates {
+        if current_repo != Some(candidate.repo.as_str()) {
+            println!("\n{} ({})", candidate.repo, candidate.local_path.display());
+            current_repo = Some(candidate.repo.as_str());
+        }
+
+        let reasons: Vec<&str> = candidate.reasons.iter().map(|r| r.as_str()).collect();
+        let protected = candidate
+            .protected
+            .as_ref()
+            .map(|why| format!(" - kept: {}", why))
+            .unwrap_or_default();
+        println!(
+            "  {} ({}, {} days old){}",
+            candidate.branch,
+            reasons.join(", "),
+            candidate.age_days,
+            protected
+        );
+    }
+
+    let deletable = report
+        .candidates
+        .iter()
+        .filter(|c| c.protected.is_none())
+        .count();
+    println!(
+        "\nFound {} branches, {} can be deleted",
+        report.candidates.len(),
+        deletable
+    );
+}
@@ -0,0 +1,292 @@
+use crate::commands::sync::{extract_repo_name, load_config, repo_identity, SyncError};
+use crate::config::{GroupConfig, RangerConfig};
+use crate::config_edit::{self, ConfigEditError, NewGroup, NewRepo};
+use crate::redact::redact;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EditError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+
+    #[error("{0}")]
+    ConfigEdit(#[from] ConfigEditError),
+
+    #[error("Failed to write configuration file: {0}")]
+    WriteFailed(#[from] std::io::Error),
+
+    #[error("Already configured: {0}")]
+    Duplicate(String),
+
+    #[error("No standalone repo matches '{0}'")]
+    NotFound(String),
+
+    #[error("'{0}' matches several repos, use the full URL: {1}")]
+    Ambiguous(String, String),
+
+    #[error("Unknown provider '{0}' (expected gitlab or github)")]
+    UnknownProvider(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct AddOptions {
+    pub config_path: PathBuf,
+    pub url: String,
+    /// `local_dir` of the new entry, relative to the workspace
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoveOptions {
+    pub config_path: PathBuf,
+    /// URL in any form, or the repo name
+    pub repo: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupAddOptions {
+    pub config_path: PathBuf,
+    /// `gitlab` or `github`
+    pub provider: String,
+    /// Group path or organization name
+    pub path: String,
+    pub recursive: bool,
+    pub dir: Option<String>,
+}
+
+/// Add a standalone repo to ranger.yaml. Refuses URLs sync already knows
+/// (in any URL form, or after `url_rewrites`), repos inside a configured
+/// GitLab group and entries that would be cloned to a taken directory.
+pub fn add_command(options: &AddOptions) -> Result<(), EditError> {
+    let config = load_config(&options.config_path)?;
+    check_new_repo(&config, &options.url, options.dir.as_deref())?;
+
+    let entry = NewRepo {
+        url: options.url.clone(),
+        local_dir: options.dir.clone(),
+    };
+    let content = fs::read_to_string(&options.config_path)?;
+    fs::write(
+        &options.config_path,
+        config_edit::add_repos(&content, &[entry])?,
+    )?;
+
+    println!("✓ Added {} to ranger.yaml", redact(&options.url));
+    println!("  Run 'git-ranger sync' to clone it");
+    Ok(())
+}
+
+/// Remove a standalone repo from ranger.yaml. The clone itself is kept.
+pub fn remove_command(options: &RemoveOptions) -> Result<(), EditError> {
+    let config = load_config(&options.config_path)?;
+    let index = find_repo(&config, &options.repo)?;
+    let url = &config.repos[index].url;
+
+    let content = fs::read_to_string(&options.config_path)?;
+    fs::write(
+        &options.config_path,
+        config_edit::remove_repo(&content, index)?,
+    )?;
+
+    println!("✓ Removed {} from ranger.yaml", redact(url));
+    println!("  The local clone was left in place");
+    Ok(())
+}
+
+/// Add a GitLab group or GitHub organization to ranger.yaml, unless it or a
+/// group overlapping it is already configured
+pub fn group_add_command(options: &GroupAddOptions) -> Result<(), EditError> {
+    let config = load_config(&options.config_path)?;
+    let groups = match options.provider.as_str() {
+        "gitlab" => &config.groups.gitlab,
+        "github" => &config.groups.github,
+        other => return Err(EditError::UnknownProvider(other.to_string())),
+    };
+
+    let path = options.path.trim_matches('/');
+    if let Some(existing) = groups
+        .iter()
+        .find(|group| overlaps(group, path, options.recursive))
+    {
+        return Err(EditError::Duplicate(format!(
+            "{} overlaps with group {}",
+            path, existing.name
+        )));
+    }
+
+    let group = NewGroup {
+        name: path.to_string(),
+        local_dir: options.dir.clone(),
+        recursive: options.recursive,
+    };
+    let content = fs::read_to_string(&options.config_path)?;
+    let content = config_edit::add_groups(&content, &options.provider, &[group])?;
+    fs::write(&options.config_path, content)?;
+
+    println!("✓ Added {} group {} to ranger.yaml", options.provider, path);
+    let configured = match options.provider.as_str() {
+        "gitlab" => config.providers.gitlab.is_some(),
+        _ => config.providers.github.is_some(),
+    };
+    if !configured {
+        eprintln!(
+            "  Note: providers.{} is not configured yet; sync needs it to list the group",
+            options.provider
+        );
+    }
+    Ok(())
+}
+
+fn check_new_repo(config: &RangerConfig, url: &str, dir: Option<&str>) -> Result<(), EditError> {
+    // Compare what sync would clone, so rewritten and original URLs match
+    let identities: Vec<String> = [url.to_string(), config.rewrite_url(url)]
+        .iter()
+        .filter_map(|url| repo_identity(url))
+        .collect();
+
+    for repo in &config.repos {
+        let existing = [repo.url.clone(), config.rewrite_url(&repo.url)];
+        if existing
+            .iter()
+            .filter_map(|url| repo_identity(url))
+            .any(|id| identities.contains(&id))
+        {
+            return Err(EditError::Duplicate(repo.url.clone()));
+        }
+    }
+
+    if let Some(group) = covering_group(config, &identities) {
+        return Err(EditError::Duplicate(format!(
+            "{} is part of group {}",
+            url, group.name
+        )));
+    }
+
+    let local_path = repo_path(dir, url);
+    if let Some(repo) = config.repos.iter().find(|repo| {
+        repo_path(repo.local_dir.as_ref().map(|dir| dir.raw()), &repo.url) == local_path
+    }) {
+        return Err(EditError::Duplicate(format!(
+            "{} would be cloned to {}, like {}",
+            url,
+            local_path.display(),
+            repo.url
+        )));
+    }
+
+    Ok(())
+}
+
+/// Configured GitLab group a repo belongs to: the one of its namespace, or
+/// a parent group with `recursive`
+fn covering_group<'a>(config: &'a RangerConfig, identities: &[String]) -> Option<&'a GroupConfig> {
+    let host = config.providers.gitlab.as_ref()?.host.resolve().ok()?;
+
+    config.groups.gitlab.iter().find(|group| {
+        let namespace =
+            match repo_identity(&format!("{}/{}", host.trim_end_matches('/'), group.name)) {
+                Some(namespace) => namespace,
+                None => return false,
+            };
+        identities.iter().any(|id| {
+            id.strip_prefix(&format!("{}/", namespace))
+                .is_some_and(|project| group.recursive || !project.contains('/'))
+        })
+    })
+}
+
+/// Where sync puts a standalone repo, relative to the workspace
+fn repo_path(local_dir: Option<&str>, url: &str) -> PathBuf {
+    let dir = local_dir
+        .unwrap_or("")
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    Path::new(dir).join(extract_repo_name(url))
+}
+
+/// Index of the standalone repo given by URL (in any form) or name
+fn find_repo(config: &RangerConfig, repo: &str) -> Result<usize, EditError> {
+    let identity = repo_identity(repo);
+    let matches: Vec<usize> = config
+        .repos
+        .iter()
+        .enumerate()
+        .filter(|(_, configured)| {
+            configured.url == repo
+                || (identity.is_some() && repo_identity(&configured.url) == identity)
+                || extract_repo_name(&configured.url) == repo
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    match matches.as_slice() {
+        [] => Err(EditError::NotFound(repo.to_string())),
+        [index] => Ok(*index),
+        _ => Err(EditError::Ambiguous(
+            repo.to_string(),
+            matches
+                .iter()
+                .map(|&i| config.repos[i].url.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )),
+    }
+}
+
+/// Whether `group` and a new group at `path` would list the same projects
+fn overlaps(group: &GroupConfig, path: &str, recursive: bool) -> bool {
+    let existing = group.name.trim_matches('/').to_ascii_lowercase();
+    let path = path.to_ascii_lowercase();
+
+    existing == path
+        || (group.recursive && path.starts_with(&format!("{}/", existing)))
+        || (recursive && existing.starts_with(&format!("{}/", path)))
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn config(yaml: &str) -> RangerConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_find_repo_by_url_form_or_name() {
+        let config = config(
+            r#"
+repos:
+  - url: "git@github.com:org/app.git"
+  - url: "https://github.com/other/app"
+  - url: "https://github.com/org/tool.git"
+"#,
+        );
+
+        assert_eq!(find_repo(&config, "https://github.com/org/app").unwrap(), 0);
+        assert_eq!(find_repo(&config, "tool").unwrap(), 2);
+        assert!(matches!(
+            find_repo(&config, "app"),
+            Err(EditError::Ambiguous(_, _))
+        ));
+        assert!(matches!(
+            find_repo(&config, "web"),
+            Err(EditError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_group_overlaps() {
+        let group = |name: &str, recursive: bool| GroupConfig {
+            recursive,
+            ..serde_yaml::from_str(&format!("name: \"{}\"", name)).unwrap()
+        };
+
+        assert!(overlaps(&group("Platform", false), "platform", false));
+        assert!(overlaps(&group("platform", true), "platform/libs", false));
+        assert!(!overlaps(&group("platform", false), "platform/libs", false));
+        assert!(overlaps(&group("platform/libs", false), "platform", true));
+        assert!(!overlaps(&group("platforms", false), "platform", true));
+    }
+}
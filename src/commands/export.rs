@@ -0,0 +1,455 @@
+use crate::commands::sync::{
+    url_host, without_credentials, workspace_repos, SyncError, WorkspaceRepo,
+};
+use crate::git;
+use crate::redact::redact;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Failed to write export file: {0}")]
+    WriteFailed(#[from] std::io::Error),
+}
+
+/// Tool the repo set is exported for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `.mrconfig` for myrepos
+    MrConfig,
+    /// `url,name,path` lines as written by `gita freeze`
+    Gita,
+    /// Manifest XML for Android's `repo` tool
+    RepoManifest,
+    /// `.code-workspace` file for VS Code
+    VsCodeWorkspace,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "mrconfig" => Ok(ExportFormat::MrConfig),
+            "gita" => Ok(ExportFormat::Gita),
+            "repo-manifest" => Ok(ExportFormat::RepoManifest),
+            "vscode-workspace" => Ok(ExportFormat::VsCodeWorkspace),
+            other => Err(format!(
+                "unknown format '{}' (expected mrconfig, gita, repo-manifest or vscode-workspace)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub config_path: PathBuf,
+    /// Same filter as `sync`: group name or part of a repo URL
+    pub target: Option<String>,
+    pub format: ExportFormat,
+    /// File to write; stdout if not set
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone)]
+#[allow(dead_code)]
+pub struct ExportReport {
+    pub repos_exported: usize,
+}
+
+#[derive(Serialize)]
+struct VsCodeWorkspace {
+    folders: Vec<VsCodeFolder>,
+}
+
+#[derive(Serialize)]
+struct VsCodeFolder {
+    name: String,
+    path: String,
+}
+
+/// Write the selected repos, after group discovery, in another tool's format.
+/// Paths in mrconfig and VS Code files are relative to the output file (the
+/// workspace when printing to stdout), manifest paths to the workspace.
+pub fn export_command(options: &ExportOptions) -> Result<ExportReport, ExportError> {
+    let mut repos = workspace_repos(&options.config_path, &options.target)?;
+    // Exported files are shared and handed to other tools: no credentials
+    for repo in &mut repos {
+        repo.clone_url = without_credentials(&repo.clone_url);
+    }
+    let base_dir = options
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let output_dir = options
+        .output
+        .as_ref()
+        .map(|output| {
+            output
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .to_path_buf()
+        })
+        .unwrap_or_else(|| base_dir.to_path_buf());
+
+    let content = match options.format {
+        ExportFormat::MrConfig => render_mrconfig(&repos, &output_dir),
+        ExportFormat::Gita => render_gita(&repos, base_dir),
+        ExportFormat::RepoManifest => render_repo_manifest(&repos, base_dir),
+        ExportFormat::VsCodeWorkspace => render_vscode_workspace(&repos, base_dir, &output_dir)?,
+    };
+
+    match options.output {
+        Some(ref output) => {
+            fs::write(output, content)?;
+            println!(
+                "✓ Exported {} repositories to {}",
+                repos.len(),
+                output.display()
+            );
+        }
+        None => print!("{}", redact(&content)),
+    }
+
+    Ok(ExportReport {
+        repos_exported: repos.len(),
+    })
+}
+
+fn render_mrconfig(repos: &[WorkspaceRepo], output_dir: &Path) -> String {
+    let mut content = String::from("# Generated by 'git-ranger export'\n");
+
+    for repo in repos {
+        let dir_name = repo
+            .local_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| repo.name.clone());
+        let mirror = if repo.mode.is_checkout() {
+            ""
+        } else {
+            "--mirror "
+        };
+
+        content.push_str(&format!(
+            "\n[{}]\ncheckout = git clone {}{} {}\n",
+            path_string(&relative_path(&repo.local_path, output_dir)),
+            mirror,
+            shell_quote(&repo.clone_url),
+            shell_quote(&dir_name)
+        ));
+    }
+
+    content
+}
+
+/// Same as `gita freeze`, so `gita clone --preserve-path --from-file` can use it
+fn render_gita(repos: &[WorkspaceRepo], base_dir: &Path) -> String {
+    let names = unique_names(repos, base_dir);
+
+    repos
+        .iter()
+        .zip(names)
+        .map(|(repo, name)| {
+            let path =
+                std::path::absolute(&repo.local_path).unwrap_or_else(|_| repo.local_path.clone());
+            format!(
+                "{},{},{}\n",
+                csv_field(&repo.clone_url),
+                csv_field(&name),
+                csv_field(&path_string(&path))
+            )
+        })
+        .collect()
+}
+
+fn render_repo_manifest(repos: &[WorkspaceRepo], base_dir: &Path) -> String {
+    let mut remotes: Vec<(String, String)> = Vec::new();
+    let mut projects = Vec::new();
+
+    for repo in repos {
+        let (fetch, name) = split_fetch_url(&repo.clone_url);
+        let remote = match remotes.iter().find(|(_, url)| *url == fetch) {
+            Some((remote, _)) => remote.clone(),
+            None => {
+                let remote = remote_name(&fetch, &remotes);
+                remotes.push((remote.clone(), fetch));
+                remote
+            }
+        };
+
+        let revision = (if repo.is_cloned() {
+            git::origin_head(&repo.local_path)
+        } else {
+            None
+        })
+        .or_else(|| repo.default_branch.clone());
+        projects.push((name, repo, remote, revision));
+    }
+
+    let mut content = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!-- Generated by 'git-ranger export' -->\n<manifest>\n");
+    for (name, fetch) in &remotes {
+        content.push_str(&format!(
+            "  <remote name=\"{}\" fetch=\"{}\" />\n",
+            xml_escape(name),
+            xml_escape(fetch)
+        ));
+    }
+
+    // repo needs a revision for every project; without one it guesses a branch
+    let default_remote = most_common(projects.iter().map(|(_, _, remote, _)| Some(remote)));
+    let default_revision =
+        most_common(projects.iter().map(|(_, _, _, revision)| revision.as_ref()));
+    if let Some(remote) = default_remote {
+        let revision = default_revision
+            .map(|revision| format!(" revision=\"{}\"", xml_escape(revision)))
+            .unwrap_or_default();
+        content.push_str(&format!(
+            "  <default remote=\"{}\"{} />\n\n",
+            xml_escape(remote),
+            revision
+        ));
+    }
+
+    for (name, repo, remote, revision) in &projects {
+        content.push_str(&format!(
+            "  <project name=\"{}\" path=\"{}\" remote=\"{}\"{} />\n",
+            xml_escape(name),
+            xml_escape(&path_string(&relative_path(&repo.local_path, base_dir))),
+            xml_escape(remote),
+            revision
+                .as_ref()
+                .map(|revision| format!(" revision=\"{}\"", xml_escape(revision)))
+                .unwrap_or_default()
+        ));
+    }
+    content.push_str("</manifest>\n");
+    content
+}
+
+/// Value given most often, the first of them on a tie
+fn most_common<'a>(values: impl Iterator<Item = Option<&'a String>>) -> Option<&'a String> {
+    let mut counts: Vec<(&String, usize)> = Vec::new();
+    for value in values.flatten() {
+        match counts.iter_mut().find(|(existing, _)| *existing == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+
+    let max = counts.iter().map(|(_, count)| *count).max()?;
+    counts
+        .into_iter()
+        .find(|(_, count)| *count == max)
+        .map(|(value, _)| value)
+}
+
+fn render_vscode_workspace(
+    repos: &[WorkspaceRepo],
+    base_dir: &Path,
+    output_dir: &Path,
+) -> Result<String, ExportError> {
+    // Mirrors have no working tree to open
+    let checkouts: Vec<WorkspaceRepo> = repos
+        .iter()
+        .filter(|repo| repo.mode.is_checkout())
+        .cloned()
+        .collect();
+    let names = unique_names(&checkouts, base_dir);
+
+    let workspace = VsCodeWorkspace {
+        folders: checkouts
+            .iter()
+            .zip(names)
+            .map(|(repo, name)| VsCodeFolder {
+                name,
+                path: path_string(&relative_path(&repo.local_path, output_dir)),
+            })
+            .collect(),
+    };
+
+    Ok(format!("{}\n", serde_json::to_string_pretty(&workspace)?))
+}
+
+/// Repo names, or their path in the workspace where two repos share a name
+fn unique_names(repos: &[WorkspaceRepo], base_dir: &Path) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for repo in repos {
+        *counts.entry(repo.name.as_str()).or_default() += 1;
+    }
+
+    repos
+        .iter()
+        .map(|repo| match counts[repo.name.as_str()] {
+            1 => repo.name.clone(),
+            _ => path_string(&relative_path(&repo.local_path, base_dir)),
+        })
+        .collect()
+}
+
+/// Split a clone URL into the `fetch` base of a manifest remote and the
+/// project name: `git@host:group/app.git` -> (`ssh://git@host`, `group/app.git`)
+fn split_fetch_url(url: &str) -> (String, String) {
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            (format!("{}://{}", scheme, authority), path.to_string())
+        }
+        None => match url.split_once(':') {
+            Some((authority, path)) => (
+                format!("ssh://{}", authority),
+                path.trim_start_matches('/').to_string(),
+            ),
+            None => (String::new(), url.to_string()),
+        },
+    }
+}
+
+/// Remote name for a fetch base: its host, made unique
+fn remote_name(fetch: &str, remotes: &[(String, String)]) -> String {
+    let host = url_host(fetch).unwrap_or_else(|| "local".to_string());
+    let mut name = host.clone();
+    let mut suffix = 2;
+    while remotes.iter().any(|(existing, _)| *existing == name) {
+        name = format!("{}-{}", host, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+/// `path` relative to `from`, both made absolute first
+fn relative_path(path: &Path, from: &Path) -> PathBuf {
+    let (path, from) = match (std::path::absolute(path), std::path::absolute(from)) {
+        (Ok(path), Ok(from)) => (normalize(&path), normalize(&from)),
+        _ => return path.to_path_buf(),
+    };
+
+    let common = path
+        .components()
+        .zip(from.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in from.components().skip(common) {
+        relative.push("..");
+    }
+    for component in path.components().skip(common) {
+        relative.push(component);
+    }
+
+    if relative.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        relative
+    }
+}
+
+/// Drop `.` components and fold `..` into its parent
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Forward slashes on every platform, as the other tools expect
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fetch_url() {
+        assert_eq!(
+            split_fetch_url("git@gitlab.example.com:group/app.git"),
+            (
+                "ssh://git@gitlab.example.com".to_string(),
+                "group/app.git".to_string()
+            )
+        );
+        assert_eq!(
+            split_fetch_url("https://github.com/org/tool"),
+            ("https://github.com".to_string(), "org/tool".to_string())
+        );
+    }
+
+    #[test]
+    fn test_most_common() {
+        let values = ["main", "trunk", "trunk", "main"].map(String::from);
+        assert_eq!(
+            most_common(values.iter().map(Some)).map(String::as_str),
+            Some("main")
+        );
+        assert_eq!(
+            most_common(values[1..].iter().map(Some)).map(String::as_str),
+            Some("trunk")
+        );
+        assert_eq!(most_common(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("/ws/team/app"), Path::new("/ws")),
+            PathBuf::from("team/app")
+        );
+        assert_eq!(
+            relative_path(Path::new("/ws/team/app"), Path::new("/ws/.vscode")),
+            PathBuf::from("../team/app")
+        );
+        assert_eq!(
+            relative_path(Path::new("/ws/./app"), Path::new("/ws/app")),
+            PathBuf::from(".")
+        );
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!(
+            "vscode-workspace".parse::<ExportFormat>(),
+            Ok(ExportFormat::VsCodeWorkspace)
+        );
+        assert!("code".parse::<ExportFormat>().is_err());
+    }
+}
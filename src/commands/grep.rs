@@ -0,0 +1,351 @@
+use crate::commands::sync::{workspace_repos, SyncError, WorkspaceRepo};
+use crate::redact::redact;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GrepError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// How results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!(
+                "unknown format '{}' (expected text or json)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrepOptions {
+    pub config_path: PathBuf,
+    /// Same filter as `sync`: group name or part of a repo URL
+    pub target: Option<String>,
+    /// Pattern as understood by `git grep`
+    pub pattern: String,
+    /// Search this ref instead of the working tree
+    pub rev: Option<String>,
+    /// Only list matching files
+    pub files_with_matches: bool,
+    pub format: OutputFormat,
+    /// Number of repos searched at once; `None` for one per CPU
+    pub jobs: Option<usize>,
+}
+
+/// One matching line, or one matching file with `files_with_matches`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GrepMatch {
+    /// Relative to the repo root
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoGrepResult {
+    pub name: String,
+    pub local_path: PathBuf,
+    pub matches: Vec<GrepMatch>,
+    /// Set when `git grep` failed, e.g. for an unknown ref
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GrepReport {
+    /// Repos with matches or errors, in workspace order
+    pub repos: Vec<RepoGrepResult>,
+    /// Configured repos that aren't cloned and were not searched
+    pub not_cloned: usize,
+}
+
+impl GrepReport {
+    pub fn total_matches(&self) -> usize {
+        self.repos.iter().map(|repo| repo.matches.len()).sum()
+    }
+
+    pub fn errors(&self) -> usize {
+        self.repos
+            .iter()
+            .filter(|repo| repo.error.is_some())
+            .count()
+    }
+}
+
+/// Run `git grep` in every selected repo, several repos at a time
+pub fn grep_command(options: &GrepOptions) -> Result<GrepReport, GrepError> {
+    let repos = workspace_repos(&options.config_path, &options.target)?;
+    let (cloned, not_cloned): (Vec<WorkspaceRepo>, Vec<WorkspaceRepo>) =
+        repos.into_iter().partition(|repo| repo.is_cloned());
+
+    let jobs = options
+        .jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(4)
+        .clamp(1, cloned.len().max(1));
+
+    let report = GrepReport {
+        repos: grep_repos(&cloned, options, jobs)
+            .into_iter()
+            .filter(|result| !result.matches.is_empty() || result.error.is_some())
+            .collect(),
+        not_cloned: not_cloned.len(),
+    };
+
+    match options.format {
+        OutputFormat::Text => print_text(&report, options.files_with_matches),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report.repos)?),
+    }
+
+    Ok(report)
+}
+
+/// Search repos on `jobs` threads, keeping results in input order
+fn grep_repos(repos: &[WorkspaceRepo], options: &GrepOptions, jobs: usize) -> Vec<RepoGrepResult> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(repos.len()));
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let repo = match repos.get(index) {
+                    Some(repo) => repo,
+                    None => break,
+                };
+
+                let result = grep_repo(repo, options);
+                if let Ok(mut results) = results.lock() {
+                    results.push((index, result));
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap_or_default();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn grep_repo(repo: &WorkspaceRepo, options: &GrepOptions) -> RepoGrepResult {
+    let mut result = RepoGrepResult {
+        name: repo.name.clone(),
+        local_path: repo.local_path.clone(),
+        matches: Vec::new(),
+        error: None,
+    };
+
+    // Mirrors have no working tree, so search what they would check out
+    let rev = options
+        .rev
+        .clone()
+        .or_else(|| (!repo.mode.is_checkout()).then(|| "HEAD".to_string()));
+
+    match run_git_grep(&repo.local_path, options, rev.as_deref()) {
+        Ok(Some(output)) => {
+            result.matches = if options.files_with_matches {
+                parse_file_names(&output, rev.as_deref())
+            } else {
+                parse_matches(&output, rev.as_deref())
+            };
+        }
+        Ok(None) => {}
+        Err(error) => result.error = Some(redact(&error)),
+    }
+
+    result
+}
+
+/// Stdout of `git grep`, `None` if nothing matched
+fn run_git_grep(
+    repo: &Path,
+    options: &GrepOptions,
+    rev: Option<&str>,
+) -> Result<Option<String>, String> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo)
+        .args(["grep", "--null", "--full-name", "--no-color", "-I"]);
+    if options.files_with_matches {
+        command.arg("--files-with-matches");
+    } else {
+        command.arg("--line-number");
+    }
+    command.arg("-e").arg(&options.pattern);
+    if let Some(rev) = rev {
+        command.arg(rev);
+    }
+    command.arg("--");
+
+    let output = command
+        .output()
+        .map_err(|e| format!("failed to run git grep: {}", e))?;
+
+    // Like grep, git grep exits with 1 when nothing matched
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+        Some(1) if output.stderr.is_empty() => Ok(None),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().next().unwrap_or("git grep failed").trim();
+            Err(message.trim_start_matches("fatal: ").to_string())
+        }
+    }
+}
+
+/// Paths from searching a ref come back as `<rev>:<path>`
+fn strip_rev<'a>(path: &'a str, rev: Option<&str>) -> &'a str {
+    rev.and_then(|rev| path.strip_prefix(rev))
+        .and_then(|path| path.strip_prefix(':'))
+        .unwrap_or(path)
+}
+
+/// Parse `git grep --null --line-number` output: `path\0line\0text` per line
+fn parse_matches(output: &str, rev: Option<&str>) -> Vec<GrepMatch> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\0');
+            let path = fields.next()?;
+            let number = fields.next()?.parse().ok()?;
+            let text = fields.next().unwrap_or("");
+
+            Some(GrepMatch {
+                path: strip_rev(path, rev).to_string(),
+                line: Some(number),
+                text: Some(text.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Parse `git grep --null --files-with-matches` output: NUL-separated paths
+fn parse_file_names(output: &str, rev: Option<&str>) -> Vec<GrepMatch> {
+    output
+        .split('\0')
+        .map(|path| path.trim_start_matches('\n'))
+        .filter(|path| !path.is_empty())
+        .map(|path| GrepMatch {
+            path: strip_rev(path, rev).to_string(),
+            line: None,
+            text: None,
+        })
+        .collect()
+}
+
+fn print_text(report: &GrepReport, files_with_matches: bool) {
+    for repo in &report.repos {
+        println!("=== {} ({}) ===", repo.name, repo.local_path.display());
+
+        if let Some(ref error) = repo.error {
+            eprintln!("✗ {}", error);
+        }
+
+        for found in &repo.matches {
+            match (files_with_matches, found.line) {
+                (false, Some(line)) => {
+                    println!(
+                        "{}:{}:{}",
+                        found.path,
+                        line,
+                        found.text.as_deref().unwrap_or("")
+                    )
+                }
+                _ => println!("{}", found.path),
+            }
+        }
+        println!();
+    }
+
+    let unit = if files_with_matches {
+        "files"
+    } else {
+        "matches"
+    };
+    println!(
+        "{} {} in {} repositories",
+        report.total_matches(),
+        unit,
+        report
+            .repos
+            .iter()
+            .filter(|repo| !repo.matches.is_empty())
+            .count()
+    );
+
+    if report.not_cloned > 0 {
+        eprintln!(
+            "{} repositories not cloned were not searched",
+            report.not_cloned
+        );
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_matches_strips_rev_and_keeps_colons_in_text() {
+        let output = "main:src/auth.rs\u{0}12\u{0}    legacyAuth(a: b)\n\
+                      main:lib/x.rs\u{0}3\u{0}legacyAuth()\n";
+
+        assert_eq!(
+            parse_matches(output, Some("main")),
+            vec![
+                GrepMatch {
+                    path: "src/auth.rs".to_string(),
+                    line: Some(12),
+                    text: Some("    legacyAuth(a: b)".to_string()),
+                },
+                GrepMatch {
+                    path: "lib/x.rs".to_string(),
+                    line: Some(3),
+                    text: Some("legacyAuth()".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_file_names() {
+        let paths: Vec<String> = parse_file_names("src/a.rs\u{0}docs/b.md\u{0}", None)
+            .into_iter()
+            .map(|found| found.path)
+            .collect();
+
+        assert_eq!(paths, vec!["src/a.rs", "docs/b.md"]);
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,464 @@
+use crate::commands::orphans::{find_repos, local_dir_for, repo_entry, ManagedRepos};
+use crate::commands::sync::{
+    load_config, provider_gitlab_client, repo_identity, url_host, url_path, SyncError,
+};
+use crate::config_edit::{self, ConfigEditError, NewGroup, NewRepo};
+use crate::git;
+use crate::providers::gitlab::GitLabClient;
+use crate::redact::redact;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Directory not found: {0}")]
+    DirectoryNotFound(String),
+
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+
+    #[error("{0}")]
+    ConfigEdit(#[from] ConfigEditError),
+
+    #[error("Failed to write configuration file: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub config_path: PathBuf,
+    /// Directory to scan for repositories
+    pub dir: PathBuf,
+    /// Only show what would be added
+    pub dry_run: bool,
+}
+
+/// What became of one repo found on disk
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportOutcome {
+    /// Added as a standalone `repos` entry
+    Repo,
+    /// Covered by a new `groups.gitlab` entry of this name
+    Group(String),
+    /// Already configured or part of a configured group
+    AlreadyManaged,
+    /// Left out for this reason
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct ImportedRepo {
+    pub path: PathBuf,
+    pub origin_url: Option<String>,
+    pub outcome: ImportOutcome,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ImportReport {
+    pub repos: Vec<ImportedRepo>,
+    /// Entries added to (or, in a dry run, that would be added to) ranger.yaml
+    pub new_repos: Vec<NewRepo>,
+    pub new_groups: Vec<NewGroup>,
+}
+
+impl ImportReport {
+    pub fn has_changes(&self) -> bool {
+        !self.new_repos.is_empty() || !self.new_groups.is_empty()
+    }
+}
+
+/// A repo to import that sync could manage as part of a GitLab group
+struct GroupCandidate {
+    /// Index into the report's repos
+    index: usize,
+    /// Namespace of the project, e.g. `["platform", "services"]`
+    namespace: Vec<String>,
+    /// Lowercased project path, e.g. `platform/services/api`
+    project: String,
+    /// Directory containing the clone
+    dir: PathBuf,
+}
+
+const HEADER: &str = "# Git Ranger Configuration\n# Generated by 'git-ranger import'. Run 'git-ranger sync' to keep these repositories up to date.\n";
+
+/// Scan `dir` for git repos and add the ones sync doesn't manage yet to
+/// ranger.yaml (creating it if needed), keeping their current layout.
+/// Repos of a GitLab namespace on the configured provider are collapsed
+/// into a group when the layout matches what sync would clone and they are
+/// all of the group's projects, so that sync clones nothing new.
+pub fn import_command(options: &ImportOptions) -> Result<ImportReport, ImportError> {
+    if !options.dir.is_dir() {
+        return Err(ImportError::DirectoryNotFound(
+            options.dir.display().to_string(),
+        ));
+    }
+
+    let base_dir = options
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let config = if options.config_path.exists() {
+        Some(load_config(&options.config_path)?)
+    } else {
+        None
+    };
+    let mode = config
+        .as_ref()
+        .map(|config| config.mode)
+        .unwrap_or_default();
+    let gitlab_host = config
+        .as_ref()
+        .and_then(|config| config.providers.gitlab.as_ref())
+        .and_then(|provider| provider.host.resolve().ok())
+        .and_then(|host| url_host(&host));
+    let managed = match config {
+        Some(ref config) => Some(ManagedRepos::load(&options.config_path, config)?),
+        None => None,
+    };
+
+    let mut found = BTreeSet::new();
+    find_repos(&options.dir, &options.dir, &mut found);
+
+    let mut report = ImportReport::default();
+    let mut entries: Vec<(usize, NewRepo)> = Vec::new();
+    let mut seen: Vec<(String, PathBuf)> = Vec::new();
+
+    for path in found {
+        let origin_url = git::config_value(&path, "remote.origin.url");
+        let outcome = match origin_url {
+            None => ImportOutcome::Skipped("no origin remote".to_string()),
+            Some(ref url)
+                if managed
+                    .as_ref()
+                    .is_some_and(|m| m.contains(&path, Some(url))) =>
+            {
+                ImportOutcome::AlreadyManaged
+            }
+            Some(ref url) => {
+                let configured = managed.as_ref().and_then(|m| m.configured_url(url));
+                let identity = repo_identity(url).unwrap_or_default();
+                let copy_of = seen
+                    .iter()
+                    .find(|(id, _)| *id == identity)
+                    .map(|(_, p)| p.clone());
+
+                match (configured, copy_of) {
+                    (Some(configured), _) => {
+                        ImportOutcome::Skipped(format!("another copy of {}", configured))
+                    }
+                    (None, Some(copy_of)) => {
+                        ImportOutcome::Skipped(format!("another copy of {}", copy_of.display()))
+                    }
+                    (None, None) => match repo_entry(&path, url.clone(), mode, base_dir) {
+                        Ok(entry) => {
+                            seen.push((identity, path.clone()));
+                            entries.push((report.repos.len(), entry));
+                            ImportOutcome::Repo
+                        }
+                        Err(reason) => ImportOutcome::Skipped(reason),
+                    },
+                }
+            }
+        };
+
+        report.repos.push(ImportedRepo {
+            path,
+            origin_url,
+            outcome,
+        });
+    }
+
+    // Repos on the GitLab provider may be covered by groups instead
+    let candidates: Vec<GroupCandidate> = entries
+        .iter()
+        .filter(|(_, entry)| gitlab_host.is_some() && url_host(&entry.url) == gitlab_host)
+        .filter_map(|(index, entry)| {
+            let project = url_path(&entry.url)?;
+            let (namespace, _) = project.rsplit_once('/')?;
+            let path = &report.repos[*index].path;
+            Some(GroupCandidate {
+                index: *index,
+                namespace: namespace.split('/').map(String::from).collect(),
+                project: project.to_ascii_lowercase(),
+                dir: path.parent().unwrap_or(base_dir).to_path_buf(),
+            })
+        })
+        .collect();
+
+    let client = match config {
+        Some(ref config) if !candidates.is_empty() => provider_gitlab_client(config, base_dir)?,
+        _ => None,
+    };
+    let covers_group = |group: &NewGroup, members: &[&GroupCandidate]| match client {
+        Some(ref client) => lists_only(client, group, members),
+        None => false,
+    };
+
+    for (group, members) in collapse_into_groups(&candidates, base_dir, covers_group) {
+        for index in members {
+            report.repos[index].outcome = ImportOutcome::Group(group.name.clone());
+        }
+        report.new_groups.push(group);
+    }
+
+    report.new_repos = entries
+        .into_iter()
+        .filter(|(index, _)| report.repos[*index].outcome == ImportOutcome::Repo)
+        .map(|(_, entry)| entry)
+        .collect();
+
+    if !options.dry_run && report.has_changes() {
+        write_entries(&options.config_path, &report)?;
+    }
+
+    print_import(&report, options.dry_run);
+    Ok(report)
+}
+
+/// Pick groups for the candidates: for each repo, the shortest namespace
+/// with at least two repos that are all where sync would clone that group,
+/// and for which `covers` confirms they are all of its projects.
+/// Returns each group with the indices of the repos it covers.
+fn collapse_into_groups(
+    candidates: &[GroupCandidate],
+    base_dir: &Path,
+    covers: impl Fn(&NewGroup, &[&GroupCandidate]) -> bool,
+) -> Vec<(NewGroup, Vec<usize>)> {
+    let mut assigned = vec![false; candidates.len()];
+    let mut groups = Vec::new();
+
+    for i in 0..candidates.len() {
+        if assigned[i] {
+            continue;
+        }
+
+        for depth in 1..=candidates[i].namespace.len() {
+            let root = &candidates[i].namespace[..depth];
+            let members: Vec<usize> = (0..candidates.len())
+                .filter(|&j| !assigned[j] && candidates[j].namespace.starts_with(root))
+                .collect();
+            // Deeper namespaces only have fewer repos
+            if members.len() < 2 {
+                break;
+            }
+
+            let group_dir = match common_group_dir(candidates, &members, depth) {
+                Some(dir) => dir,
+                None => continue,
+            };
+
+            let recursive = members
+                .iter()
+                .any(|&j| candidates[j].namespace.len() > depth);
+            // Without a local_dir, sync puts subgroup repos in the workspace root
+            let local_dir =
+                local_dir_for(&group_dir, base_dir).or_else(|| recursive.then(|| ".".to_string()));
+            let group = NewGroup {
+                name: root.join("/"),
+                local_dir,
+                recursive,
+            };
+
+            // Otherwise sync would also clone the group's other projects
+            let member_candidates: Vec<&GroupCandidate> =
+                members.iter().map(|&j| &candidates[j]).collect();
+            if !covers(&group, &member_candidates) {
+                continue;
+            }
+
+            for &j in &members {
+                assigned[j] = true;
+            }
+            groups.push((
+                group,
+                members.iter().map(|&j| candidates[j].index).collect(),
+            ));
+            break;
+        }
+    }
+
+    groups
+}
+
+/// Directory a group rooted at `namespace[..depth]` would need so that sync
+/// clones every member where it already is: `<dir>/<subgroups>/<repo>`
+fn common_group_dir(
+    candidates: &[GroupCandidate],
+    members: &[usize],
+    depth: usize,
+) -> Option<PathBuf> {
+    let mut common: Option<PathBuf> = None;
+
+    for &j in members {
+        let mut dir = candidates[j].dir.as_path();
+        for subgroup in candidates[j].namespace[depth..].iter().rev() {
+            if dir.file_name().and_then(|name| name.to_str()) != Some(subgroup.as_str()) {
+                return None;
+            }
+            dir = dir.parent()?;
+        }
+
+        match common {
+            Some(ref common) if common != dir => return None,
+            Some(_) => {}
+            None => common = Some(dir.to_path_buf()),
+        }
+    }
+
+    common
+}
+
+/// Whether the group lists no projects besides the members'
+fn lists_only(client: &GitLabClient, group: &NewGroup, members: &[&GroupCandidate]) -> bool {
+    match client.get_group_projects(&group.name, group.recursive) {
+        Ok(projects) => projects.iter().all(|project| {
+            let path = project.path_with_namespace.to_ascii_lowercase();
+            members.iter().any(|member| member.project == path)
+        }),
+        Err(e) => {
+            eprintln!(
+                "Warning: Failed to get projects for group '{}': {}",
+                group.name,
+                redact(&e.to_string())
+            );
+            false
+        }
+    }
+}
+
+fn write_entries(config_path: &Path, report: &ImportReport) -> Result<(), ImportError> {
+    let mut content = if config_path.exists() {
+        fs::read_to_string(config_path)?
+    } else {
+        HEADER.to_string()
+    };
+
+    if !report.new_groups.is_empty() {
+        content = config_edit::add_groups(&content, "gitlab", &report.new_groups)?;
+    }
+    if !report.new_repos.is_empty() {
+        content = config_edit::add_repos(&content, &report.new_repos)?;
+    }
+
+    fs::write(config_path, content)?;
+    Ok(())
+}
+
+fn print_import(report: &ImportReport, dry_run: bool) {
+    println!("\n=== Import ===");
+
+    for repo in &report.repos {
+        let outcome = match repo.outcome {
+            ImportOutcome::Repo => "repo".to_string(),
+            ImportOutcome::Group(ref name) => format!("group {}", name),
+            ImportOutcome::AlreadyManaged => "already managed".to_string(),
+            ImportOutcome::Skipped(ref reason) => format!("skipped: {}", reason),
+        };
+        println!("  {} ({})", repo.path.display(), redact(&outcome));
+    }
+
+    if !report.has_changes() {
+        println!("\nNothing to import");
+        return;
+    }
+
+    if !report.new_groups.is_empty() {
+        println!("\nGroups (sync will clone every project in them):");
+        for group in &report.new_groups {
+            let recursive = if group.recursive { ", recursive" } else { "" };
+            println!(
+                "  {} -> {}{}",
+                group.name,
+                group.local_dir.as_deref().unwrap_or("."),
+                recursive
+            );
+        }
+    }
+
+    let verb = if dry_run { "Would add" } else { "Added" };
+    println!(
+        "\n{} {} repositories and {} groups to ranger.yaml",
+        verb,
+        report.new_repos.len(),
+        report.new_groups.len()
+    );
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn candidate(index: usize, namespace: &str, dir: &str) -> GroupCandidate {
+        GroupCandidate {
+            index,
+            namespace: namespace.split('/').map(String::from).collect(),
+            project: format!("{}/repo{}", namespace, index),
+            dir: PathBuf::from(dir),
+        }
+    }
+
+    fn covers_all(_: &NewGroup, _: &[&GroupCandidate]) -> bool {
+        true
+    }
+
+    #[test]
+    fn test_collapse_uses_the_shortest_namespace_matching_the_layout() {
+        let candidates = vec![
+            candidate(0, "platform", "/src/platform"),
+            candidate(1, "platform/libs", "/src/platform/libs"),
+            candidate(2, "platform/libs", "/src/platform/libs"),
+            candidate(3, "tools", "/src/tools"),
+        ];
+
+        let groups = collapse_into_groups(&candidates, Path::new("/src"), covers_all);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].0,
+            NewGroup {
+                name: "platform".to_string(),
+                local_dir: Some("platform".to_string()),
+                recursive: true,
+            }
+        );
+        assert_eq!(groups[0].1, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_collapse_falls_back_to_subgroups_when_layout_differs() {
+        let candidates = vec![
+            candidate(0, "platform/api", "/src/api"),
+            candidate(1, "platform/api", "/src/api"),
+            candidate(2, "platform/web", "/src/frontend"),
+        ];
+
+        let groups = collapse_into_groups(&candidates, Path::new("/src"), covers_all);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0.name, "platform/api");
+        assert_eq!(groups[0].0.local_dir.as_deref(), Some("api"));
+        assert!(!groups[0].0.recursive);
+        assert_eq!(groups[0].1, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_collapse_skips_groups_with_other_projects() {
+        let candidates = vec![
+            candidate(0, "platform", "/src/platform"),
+            candidate(1, "platform/libs", "/src/platform/libs"),
+            candidate(2, "platform/libs", "/src/platform/libs"),
+        ];
+
+        // `platform` has more projects than were cloned
+        let groups = collapse_into_groups(&candidates, Path::new("/src"), |group, _| {
+            group.name != "platform"
+        });
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0.name, "platform/libs");
+        assert_eq!(groups[0].1, vec![1, 2]);
+    }
+}
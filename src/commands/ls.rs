@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 use thiserror::Error;
-use crate::config::{RangerConfig, ConfigLoadError, RepoConfig};
+use crate::config::{RangerConfig, ConfigLoadError, RepoConfig, EnvResolutionError, SyncMode};
+use crate::redact::redact;
 
 #[derive(Error, Debug)]
 pub enum LsError {
@@ -15,6 +16,9 @@ pub enum LsError {
     
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Invalid configuration value: {0}")]
+    ConfigValueError(#[from] EnvResolutionError),
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +30,8 @@ pub struct LsOptions {
 pub struct RepoInfo {
     pub name: String,
     pub url: String,
+    /// `url` after applying `url_rewrites`
+    pub clone_url: String,
     pub local_path: PathBuf,
 }
 
@@ -36,8 +42,18 @@ pub fn ls_command(options: &LsOptions) -> Result<Vec<RepoInfo>, LsError> {
     let mut repos = Vec::new();
     
     // List standalone repos
-    for repo_config in config.get_standalone_repos() {
-        let repo_info = build_repo_info(repo_config, base_dir)?;
+    for (index, repo_config) in config.get_standalone_repos().iter().enumerate() {
+        let repo_info =
+            build_repo_info(repo_config, base_dir, config.mode).map_err(|e| match e {
+                LsError::ConfigValueError(e) => {
+                    LsError::ConfigValueError(e.for_key(format!("repos[{}].local_dir", index)))
+                }
+                other => other,
+            })?;
+        let repo_info = RepoInfo {
+            clone_url: config.rewrite_url(&repo_info.url),
+            ..repo_info
+        };
         repos.push(repo_info);
     }
     
@@ -62,13 +78,15 @@ fn load_config(config_path: &Path) -> Result<RangerConfig, LsError> {
 fn build_repo_info(
     repo_config: &RepoConfig,
     base_dir: &Path,
+    mode: SyncMode,
 ) -> Result<RepoInfo, LsError> {
     let repo_name = extract_repo_name(&repo_config.url);
-    let local_path = build_local_path(repo_config, base_dir, &repo_name);
+    let local_path = build_local_path(repo_config, base_dir, &mode.repo_dir_name(&repo_name))?;
     
     Ok(RepoInfo {
         name: repo_name,
         url: repo_config.url.clone(),
+        clone_url: repo_config.url.clone(),
         local_path,
     })
 }
@@ -96,20 +114,20 @@ fn build_local_path(
     repo_config: &RepoConfig,
     base_dir: &Path,
     repo_name: &str,
-) -> PathBuf {
+) -> Result<PathBuf, EnvResolutionError> {
     let local_dir = match &repo_config.local_dir {
         Some(dir) => {
-            let dir_path = PathBuf::from(dir);
+            let dir_path = PathBuf::from(dir.resolve_path()?);
             if dir_path.is_absolute() {
                 dir_path
             } else {
-                base_dir.join(dir)
+                base_dir.join(dir_path)
             }
         }
         None => base_dir.to_path_buf(),
     };
     
-    local_dir.join(repo_name)
+    Ok(local_dir.join(repo_name))
 }
 
 fn print_repo_listing(repos: &[RepoInfo]) {
@@ -123,7 +141,10 @@ fn print_repo_listing(repos: &[RepoInfo]) {
     
     for repo in repos {
         println!("{}", repo.name);
-        println!("  URL: {}", repo.url);
+        println!("  URL: {}", redact(&repo.url));
+        if repo.clone_url != repo.url {
+            println!("  Clone URL: {}", redact(&repo.clone_url));
+        }
         println!("  Local Path: {}", repo.local_path.display());
         println!();
     }
@@ -161,12 +182,13 @@ mod unit_tests {
     fn test_build_local_path_with_relative_dir() {
         let repo_config = RepoConfig {
             url: "https://github.com/user/repo.git".to_string(),
-            local_dir: Some("projects".to_string()),
+            local_dir: Some("projects".into()),
+            ..Default::default()
         };
         let base_dir = Path::new("/home/user/workspace");
         let repo_name = "repo";
         
-        let path = build_local_path(&repo_config, base_dir, repo_name);
+        let path = build_local_path(&repo_config, base_dir, repo_name).unwrap();
         
         assert_eq!(path, PathBuf::from("/home/user/workspace/projects/repo"));
     }
@@ -176,11 +198,12 @@ mod unit_tests {
         let repo_config = RepoConfig {
             url: "https://github.com/user/repo.git".to_string(),
             local_dir: None,
+            ..Default::default()
         };
         let base_dir = Path::new("/home/user/workspace");
         let repo_name = "repo";
         
-        let path = build_local_path(&repo_config, base_dir, repo_name);
+        let path = build_local_path(&repo_config, base_dir, repo_name).unwrap();
         
         assert_eq!(path, PathBuf::from("/home/user/workspace/repo"));
     }
@@ -189,11 +212,12 @@ mod unit_tests {
     fn test_build_repo_info_complete() {
         let repo_config = RepoConfig {
             url: "https://github.com/user/awesome-project.git".to_string(),
-            local_dir: Some("projects".to_string()),
+            local_dir: Some("projects".into()),
+            ..Default::default()
         };
         let base_dir = Path::new("/home/user/workspace");
         
-        let info = build_repo_info(&repo_config, base_dir).unwrap();
+        let info = build_repo_info(&repo_config, base_dir, SyncMode::Checkout).unwrap();
         
         assert_eq!(info.name, "awesome-project");
         assert_eq!(info.url, "https://github.com/user/awesome-project.git");
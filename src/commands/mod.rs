@@ -1,4 +1,12 @@
+pub mod audit;
+pub mod branch;
+pub mod branches;
+pub mod edit;
+pub mod export;
+pub mod grep;
+pub mod import;
 pub mod init;
 pub mod ls;
+pub mod orphans;
 pub mod status;
 pub mod sync;
@@ -0,0 +1,373 @@
+use crate::commands::sync::{
+    extract_repo_name, load_config, repo_identity, without_credentials, workspace_repos, SyncError,
+    WorkspaceRepo,
+};
+use crate::config::{RangerConfig, SyncMode};
+use crate::config_edit::{self, ConfigEditError, NewRepo};
+use crate::git;
+use crate::redact::redact;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OrphansError {
+    #[error("{0}")]
+    WorkspaceError(#[from] SyncError),
+
+    #[error("{0}")]
+    ConfigEdit(#[from] ConfigEditError),
+
+    #[error("Failed to update configuration file: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct OrphansOptions {
+    pub config_path: PathBuf,
+    /// Add the repos found to ranger.yaml as standalone `repos` entries
+    pub add: bool,
+}
+
+/// A git repo on disk that no configured or discovered repo accounts for
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct OrphanRepo {
+    pub path: PathBuf,
+    /// `remote.origin.url`, if the repo has an origin
+    pub origin_url: Option<String>,
+    /// Configured repo this is another copy of
+    pub duplicate_of: Option<String>,
+    pub added: bool,
+    /// Why the repo could not be added to the config
+    pub not_added: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct OrphansReport {
+    pub orphans: Vec<OrphanRepo>,
+}
+
+impl OrphansReport {
+    /// Whether any repo found is still unmanaged
+    pub fn has_findings(&self) -> bool {
+        self.orphans.iter().any(|orphan| !orphan.added)
+    }
+}
+
+/// Find git repos inside the workspace's `local_dir` trees that sync
+/// doesn't manage, and optionally add them to ranger.yaml
+pub fn orphans_command(options: &OrphansOptions) -> Result<OrphansReport, OrphansError> {
+    let config = load_config(&options.config_path)?;
+    let base_dir = options
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let managed = ManagedRepos::load(&options.config_path, &config)?;
+
+    let mut found = BTreeSet::new();
+    for root in search_roots(&config, base_dir) {
+        find_repos(&root, base_dir, &mut found);
+    }
+
+    let mut report = OrphansReport::default();
+    for path in found {
+        let origin_url = git::config_value(&path, "remote.origin.url");
+        if managed.contains(&path, origin_url.as_deref()) {
+            continue;
+        }
+
+        report.orphans.push(OrphanRepo {
+            duplicate_of: origin_url
+                .as_deref()
+                .and_then(|url| managed.configured_url(url)),
+            path,
+            origin_url,
+            added: false,
+            not_added: None,
+        });
+    }
+
+    if options.add {
+        add_orphans(&options.config_path, &config, base_dir, &mut report)?;
+    }
+
+    print_orphans(&report, options.add);
+    Ok(report)
+}
+
+/// The repos sync accounts for, to tell them apart from others on disk
+pub(crate) struct ManagedRepos {
+    repos: Vec<WorkspaceRepo>,
+    paths: BTreeSet<PathBuf>,
+    /// `host/group/` prefixes of the repo identities of configured GitLab groups
+    namespaces: Vec<String>,
+}
+
+impl ManagedRepos {
+    pub(crate) fn load(config_path: &Path, config: &RangerConfig) -> Result<Self, SyncError> {
+        let repos = workspace_repos(config_path, &None)?;
+        let paths = repos
+            .iter()
+            .map(|repo| canonical(&repo.local_path))
+            .collect();
+
+        Ok(ManagedRepos {
+            repos,
+            paths,
+            namespaces: group_namespaces(config),
+        })
+    }
+
+    /// Whether sync manages the repo at `path`. Repos in the namespace of a
+    /// configured group count as managed even when discovery failed.
+    pub(crate) fn contains(&self, path: &Path, origin_url: Option<&str>) -> bool {
+        if self.paths.contains(&canonical(path)) {
+            return true;
+        }
+
+        origin_url.and_then(repo_identity).is_some_and(|id| {
+            self.namespaces
+                .iter()
+                .any(|namespace| id.starts_with(namespace))
+        })
+    }
+
+    /// URL of the configured repo `url` is another copy of
+    pub(crate) fn configured_url(&self, url: &str) -> Option<String> {
+        let identity = repo_identity(url)?;
+        self.repos
+            .iter()
+            .find(|repo| {
+                [&repo.url, &repo.clone_url]
+                    .iter()
+                    .any(|url| repo_identity(url).as_ref() == Some(&identity))
+            })
+            .map(|repo| repo.url.clone())
+    }
+}
+
+/// Directories repos are cloned into: every `local_dir`, and the workspace
+/// itself if any repo or group has none
+fn search_roots(config: &RangerConfig, base_dir: &Path) -> Vec<PathBuf> {
+    let local_dirs = config
+        .groups
+        .gitlab
+        .iter()
+        .chain(config.groups.github.iter())
+        .map(|group| group.local_dir.as_ref())
+        .chain(config.repos.iter().map(|repo| repo.local_dir.as_ref()));
+
+    let mut roots = Vec::new();
+    for local_dir in local_dirs {
+        let root = match local_dir {
+            Some(dir) => match dir.resolve_path() {
+                Ok(dir) => base_dir.join(dir),
+                Err(_) => continue,
+            },
+            None => base_dir.to_path_buf(),
+        };
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// `host/group/` prefixes of the repo identities of configured GitLab groups
+fn group_namespaces(config: &RangerConfig) -> Vec<String> {
+    let host = match config
+        .providers
+        .gitlab
+        .as_ref()
+        .and_then(|p| p.host.resolve().ok())
+    {
+        Some(host) => host,
+        None => return Vec::new(),
+    };
+
+    config
+        .groups
+        .gitlab
+        .iter()
+        .filter_map(|group| {
+            repo_identity(&format!("{}/{}", host.trim_end_matches('/'), group.name))
+        })
+        .map(|namespace| format!("{}/", namespace))
+        .collect()
+}
+
+/// Collect git repos under `dir`, without looking inside them. Hidden
+/// directories (like `.git-ranger`) and symlinks are skipped. A workspace
+/// kept in a repo of its own is searched like a plain directory.
+pub(crate) fn find_repos(dir: &Path, workspace: &Path, found: &mut BTreeSet<PathBuf>) {
+    if is_repo(dir) && canonical(dir) != canonical(workspace) {
+        found.insert(dir.to_path_buf());
+        return;
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir && !hidden {
+            find_repos(&entry.path(), workspace, found);
+        }
+    }
+}
+
+/// A working tree (`.git` directory or file) or a bare repository
+fn is_repo(dir: &Path) -> bool {
+    dir.join(".git").exists() || is_bare_repo(dir)
+}
+
+fn is_bare_repo(dir: &Path) -> bool {
+    dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Append the orphans sync could take over unchanged to the config
+fn add_orphans(
+    config_path: &Path,
+    config: &RangerConfig,
+    base_dir: &Path,
+    report: &mut OrphansReport,
+) -> Result<(), OrphansError> {
+    let mut new_repos: Vec<NewRepo> = Vec::new();
+
+    for orphan in &mut report.orphans {
+        let entry = new_repo_entry(orphan, config, base_dir).and_then(|entry| {
+            // Clones of one remote, e.g. over ssh and https, are added once
+            let identity = repo_identity(&entry.url);
+            match new_repos
+                .iter()
+                .find(|added| identity.is_some() && repo_identity(&added.url) == identity)
+            {
+                Some(added) => Err(format!("another clone of {}", added.url)),
+                None => Ok(entry),
+            }
+        });
+        match entry {
+            Ok(entry) => {
+                new_repos.push(entry);
+                orphan.added = true;
+            }
+            Err(reason) => orphan.not_added = Some(reason),
+        }
+    }
+
+    if !new_repos.is_empty() {
+        let content = fs::read_to_string(config_path)?;
+        fs::write(config_path, config_edit::add_repos(&content, &new_repos)?)?;
+    }
+
+    Ok(())
+}
+
+fn new_repo_entry(
+    orphan: &OrphanRepo,
+    config: &RangerConfig,
+    base_dir: &Path,
+) -> Result<NewRepo, String> {
+    let url = orphan
+        .origin_url
+        .clone()
+        .ok_or_else(|| "no origin remote".to_string())?;
+    if let Some(ref configured) = orphan.duplicate_of {
+        return Err(format!("another copy of {}", configured));
+    }
+
+    repo_entry(&orphan.path, url, config.mode, base_dir)
+}
+
+/// The `repos` entry that makes sync use the repo at `path` where it is.
+/// Credentials in the origin URL are left out of the config.
+pub(crate) fn repo_entry(
+    path: &Path,
+    url: String,
+    mode: SyncMode,
+    base_dir: &Path,
+) -> Result<NewRepo, String> {
+    if !mode.is_cloned(path) {
+        return Err(if mode.is_checkout() {
+            "bare repository in a checkout workspace".to_string()
+        } else {
+            "working tree in a mirror workspace".to_string()
+        });
+    }
+
+    // Sync derives the directory name from the URL
+    let expected = mode.repo_dir_name(&extract_repo_name(&url));
+    if path.file_name().and_then(|name| name.to_str()) != Some(expected.as_str()) {
+        return Err(format!(
+            "directory name differs from the repository name ({})",
+            expected
+        ));
+    }
+
+    Ok(NewRepo {
+        url: without_credentials(&url),
+        local_dir: local_dir_for(path.parent().unwrap_or(base_dir), base_dir),
+    })
+}
+
+/// `local_dir` value for repos in `dir`: relative to the workspace where
+/// possible, `None` for the workspace itself
+pub(crate) fn local_dir_for(dir: &Path, base_dir: &Path) -> Option<String> {
+    let relative = dir
+        .strip_prefix(base_dir)
+        .map(Path::to_path_buf)
+        .or_else(|_| {
+            canonical(dir)
+                .strip_prefix(canonical(base_dir))
+                .map(Path::to_path_buf)
+        });
+
+    match relative {
+        Ok(relative) if relative.as_os_str().is_empty() => None,
+        Ok(relative) => Some(relative.to_string_lossy().into_owned()),
+        Err(_) => Some(dir.to_string_lossy().into_owned()),
+    }
+}
+
+fn print_orphans(report: &OrphansReport, add: bool) {
+    println!("\n=== Unmanaged Repositories ===");
+
+    for orphan in &report.orphans {
+        let origin = orphan.origin_url.as_deref().unwrap_or("no origin");
+        println!("  {} ({})", orphan.path.display(), redact(origin));
+
+        if let Some(ref configured) = orphan.duplicate_of {
+            println!("    another copy of {}", redact(configured));
+        }
+        if orphan.added {
+            println!("    ✓ added to ranger.yaml");
+        } else if let Some(ref reason) = orphan.not_added {
+            println!("    ✗ not added: {}", redact(reason));
+        }
+    }
+
+    if report.orphans.is_empty() {
+        println!("No unmanaged repositories found");
+    } else if add {
+        let added = report.orphans.iter().filter(|orphan| orphan.added).count();
+        println!(
+            "\nAdded {} of {} repositories to ranger.yaml",
+            added,
+            report.orphans.len()
+        );
+    } else {
+        println!(
+            "\nFound {} unmanaged repositories; run `git-ranger orphans --add` to add them",
+            report.orphans.len()
+        );
+    }
+}
@@ -1,6 +1,9 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use thiserror::Error;
-use crate::config::{RangerConfig, ConfigLoadError, RepoConfig};
+use crate::commands::sync::{workspace_repos, SyncError, WorkspaceRepo};
+use crate::config::{ConfigLoadError, EnvResolutionError, SubmoduleMode, LfsMode};
+use crate::git;
+use crate::redact::redact;
 
 #[derive(Error, Debug)]
 pub enum StatusError {
@@ -15,6 +18,27 @@ pub enum StatusError {
     
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Invalid configuration value: {0}")]
+    ConfigValueError(#[from] EnvResolutionError),
+
+    #[error("{0}")]
+    WorkspaceError(SyncError),
+}
+
+/// Config errors keep their own variants; anything from group discovery
+/// is passed on as is
+impl From<SyncError> for StatusError {
+    fn from(error: SyncError) -> Self {
+        match error {
+            SyncError::ConfigNotFound(path) => StatusError::ConfigNotFound(path),
+            SyncError::ConfigParseError(msg) => StatusError::ConfigParseError(msg),
+            SyncError::ConfigLoadError(e) => StatusError::ConfigLoadError(e),
+            SyncError::IoError(e) => StatusError::IoError(e),
+            SyncError::ConfigValueError(e) => StatusError::ConfigValueError(e),
+            other => StatusError::WorkspaceError(other),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,8 +49,24 @@ pub struct StatusOptions {
 #[derive(Debug, Clone)]
 pub struct RepoStatus {
     pub name: String,
+    pub url: String,
+    /// `url` after applying `url_rewrites`
+    pub clone_url: String,
     pub local_path: PathBuf,
     pub cloned: bool,
+    /// Submodules that are registered but not checked out
+    pub missing_submodules: Vec<String>,
+    /// LFS files that are still pointers
+    pub missing_lfs_objects: usize,
+    /// `git_config` settings that differ from the repo's `.git/config`
+    pub git_config_drift: Vec<git::ConfigDrift>,
+}
+
+impl RepoStatus {
+    /// Cloned, but missing submodules or LFS objects
+    pub fn is_incomplete(&self) -> bool {
+        !self.missing_submodules.is_empty() || self.missing_lfs_objects > 0
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -34,6 +74,8 @@ pub struct StatusReport {
     pub total_repos: usize,
     pub repos_cloned: usize,
     pub repos_not_cloned: usize,
+    pub repos_incomplete: usize,
+    pub repos_with_config_drift: usize,
     pub repos: Vec<RepoStatus>,
 }
 
@@ -43,18 +85,21 @@ impl StatusReport {
     }
 }
 
+/// Status of every repo sync manages, groups included
 pub fn status_command(options: &StatusOptions) -> Result<StatusReport, StatusError> {
-    let config = load_config(&options.config_path)?;
-    let base_dir = options.config_path.parent().unwrap_or_else(|| Path::new("."));
-    
     let mut report = StatusReport::new();
     
-    // Analyze standalone repos
-    for repo_config in config.get_standalone_repos() {
-        let repo_status = analyze_repo_status(repo_config, base_dir)?;
+    for repo in workspace_repos(&options.config_path, &None)? {
+        let repo_status = analyze_repo_status(repo);
         
         if repo_status.cloned {
             report.repos_cloned += 1;
+            if repo_status.is_incomplete() {
+                report.repos_incomplete += 1;
+            }
+            if !repo_status.git_config_drift.is_empty() {
+                report.repos_with_config_drift += 1;
+            }
         } else {
             report.repos_not_cloned += 1;
         }
@@ -69,73 +114,38 @@ pub fn status_command(options: &StatusOptions) -> Result<StatusReport, StatusErr
     Ok(report)
 }
 
-fn load_config(config_path: &Path) -> Result<RangerConfig, StatusError> {
-    if !config_path.exists() {
-        return Err(StatusError::ConfigNotFound(config_path.display().to_string()));
-    }
-    
-    RangerConfig::load_from_file(config_path)
-        .map_err(|e| match e {
-            ConfigLoadError::ParseError(msg) => StatusError::ConfigParseError(msg),
-            other => StatusError::ConfigLoadError(other),
-        })
-}
-
-fn analyze_repo_status(
-    repo_config: &RepoConfig,
-    base_dir: &Path,
-) -> Result<RepoStatus, StatusError> {
-    let repo_name = extract_repo_name(&repo_config.url);
-    let local_path = build_local_path(repo_config, base_dir, &repo_name);
-    
-    // Check if repo is cloned (has .git directory)
-    let git_dir = local_path.join(".git");
-    let cloned = git_dir.exists();
-    
-    Ok(RepoStatus {
-        name: repo_name,
-        local_path,
-        cloned,
-    })
-}
-
-fn extract_repo_name(url: &str) -> String {
-    // Extract repo name from URL
-    // Examples:
-    // - https://github.com/user/repo.git -> repo
-    // - git@github.com:user/repo.git -> repo
-    // - https://gitlab.com/org/project -> project
-    
-    let url = url.trim_end_matches('/');
-    let url = url.trim_end_matches(".git");
-    
-    url.rsplit('/')
-        .next()
-        .unwrap_or("unknown")
-        .rsplit(':')
-        .next()
-        .unwrap_or("unknown")
-        .to_string()
-}
+fn analyze_repo_status(repo: WorkspaceRepo) -> RepoStatus {
+    // Check if repo is cloned (has .git directory, or is a bare mirror)
+    let cloned = repo.is_cloned();
+    let checked_out = cloned && repo.mode.is_checkout();
 
-fn build_local_path(
-    repo_config: &RepoConfig,
-    base_dir: &Path,
-    repo_name: &str,
-) -> PathBuf {
-    let local_dir = match &repo_config.local_dir {
-        Some(dir) => {
-            let dir_path = PathBuf::from(dir);
-            if dir_path.is_absolute() {
-                dir_path
-            } else {
-                base_dir.join(dir)
-            }
-        }
-        None => base_dir.to_path_buf(),
+    // Only look for what the repo is configured to have checked out
+    let missing_submodules = if checked_out && repo.submodules != Some(SubmoduleMode::None) {
+        git::missing_submodules(&repo.local_path, true)
+    } else {
+        Vec::new()
+    };
+    let missing_lfs_objects = if checked_out && repo.lfs != Some(LfsMode::Skip) {
+        git::missing_lfs_objects(&repo.local_path).unwrap_or(0)
+    } else {
+        0
+    };
+    let git_config_drift = if checked_out {
+        git::config_drift(&repo.local_path, &repo.git_config)
+    } else {
+        Vec::new()
     };
     
-    local_dir.join(repo_name)
+    RepoStatus {
+        name: repo.name,
+        url: repo.url,
+        clone_url: repo.clone_url,
+        local_path: repo.local_path,
+        cloned,
+        missing_submodules,
+        missing_lfs_objects,
+        git_config_drift,
+    }
 }
 
 fn print_status_report(report: &StatusReport) {
@@ -143,6 +153,12 @@ fn print_status_report(report: &StatusReport) {
     println!("Total repositories: {}", report.total_repos);
     println!("Cloned: {}", report.repos_cloned);
     println!("Not cloned: {}", report.repos_not_cloned);
+    if report.repos_incomplete > 0 {
+        println!("Incomplete: {}", report.repos_incomplete);
+    }
+    if report.repos_with_config_drift > 0 {
+        println!("Git config drift: {}", report.repos_with_config_drift);
+    }
     println!();
     
     if report.repos.is_empty() {
@@ -160,62 +176,113 @@ fn print_status_report(report: &StatusReport) {
             status_text,
             repo.local_path.display()
         );
+        
+        if repo.clone_url != repo.url {
+            println!(
+                "    URL: {} => {}",
+                redact(&repo.url),
+                redact(&repo.clone_url)
+            );
+        }
+        
+        if !repo.missing_submodules.is_empty() {
+            println!(
+                "    Missing submodules: {}",
+                repo.missing_submodules.join(", ")
+            );
+        }
+        
+        if repo.missing_lfs_objects > 0 {
+            println!("    Missing LFS objects: {}", repo.missing_lfs_objects);
+        }
+        
+        for drift in &repo.git_config_drift {
+            println!(
+                "    Git config {}: {} (expected {})",
+                drift.key,
+                redact(drift.actual.as_deref().unwrap_or("unset")),
+                redact(&drift.expected)
+            );
+        }
     }
-    
+
     println!();
 }
 
 #[cfg(test)]
 mod unit_tests {
-    use super::*;
-    
+    use crate::commands::sync::{build_local_path, extract_repo_name};
+    use crate::config::RepoConfig;
+    use std::path::{Path, PathBuf};
+
     #[test]
     fn test_extract_repo_name_from_https_url() {
-        assert_eq!(extract_repo_name("https://github.com/user/my-repo.git"), "my-repo");
-        assert_eq!(extract_repo_name("https://gitlab.com/org/project.git"), "project");
+        assert_eq!(
+            extract_repo_name("https://github.com/user/my-repo.git"),
+            "my-repo"
+        );
+        assert_eq!(
+            extract_repo_name("https://gitlab.com/org/project.git"),
+            "project"
+        );
     }
-    
+
     #[test]
     fn test_extract_repo_name_from_ssh_url() {
-        assert_eq!(extract_repo_name("git@github.com:user/my-repo.git"), "my-repo");
-        assert_eq!(extract_repo_name("git@gitlab.com:org/project.git"), "project");
+        assert_eq!(
+            extract_repo_name("git@github.com:user/my-repo.git"),
+            "my-repo"
+        );
+        assert_eq!(
+            extract_repo_name("git@gitlab.com:org/project.git"),
+            "project"
+        );
+        assert_eq!(extract_repo_name("git@host:my-repo.git:"), "my-repo");
     }
-    
+
     #[test]
     fn test_extract_repo_name_without_git_extension() {
-        assert_eq!(extract_repo_name("https://github.com/user/my-repo"), "my-repo");
+        assert_eq!(
+            extract_repo_name("https://github.com/user/my-repo"),
+            "my-repo"
+        );
     }
-    
+
     #[test]
     fn test_extract_repo_name_with_trailing_slash() {
-        assert_eq!(extract_repo_name("https://github.com/user/my-repo.git/"), "my-repo");
+        assert_eq!(
+            extract_repo_name("https://github.com/user/my-repo.git/"),
+            "my-repo"
+        );
     }
-    
+
     #[test]
     fn test_build_local_path_with_relative_dir() {
         let repo_config = RepoConfig {
             url: "https://github.com/user/repo.git".to_string(),
-            local_dir: Some("projects".to_string()),
+            local_dir: Some("projects".into()),
+            ..Default::default()
         };
         let base_dir = Path::new("/home/user/workspace");
         let repo_name = "repo";
-        
-        let path = build_local_path(&repo_config, base_dir, repo_name);
-        
+
+        let path = build_local_path(&repo_config, base_dir, repo_name).unwrap();
+
         assert_eq!(path, PathBuf::from("/home/user/workspace/projects/repo"));
     }
-    
+
     #[test]
     fn test_build_local_path_without_local_dir() {
         let repo_config = RepoConfig {
             url: "https://github.com/user/repo.git".to_string(),
             local_dir: None,
+            ..Default::default()
         };
         let base_dir = Path::new("/home/user/workspace");
         let repo_name = "repo";
-        
-        let path = build_local_path(&repo_config, base_dir, repo_name);
-        
+
+        let path = build_local_path(&repo_config, base_dir, repo_name).unwrap();
+
         assert_eq!(path, PathBuf::from("/home/user/workspace/repo"));
     }
 }
@@ -1,10 +1,17 @@
+use std::collections::BTreeMap;
 use std::env;
 use serde::{Deserialize, Serialize};
 use serde::de::{self, Deserializer, Visitor};
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::credentials::{expand_home, CredentialSource};
+use crate::redact;
 
-/// A string value that can be resolved from an environment variable
-/// Supports syntax: ${ENV_VAR_NAME} or direct string values
+/// A string value that can be resolved from environment variables
+/// Supports `${VAR}` anywhere in the string, `${VAR:-default}` fallbacks and
+/// `$$` for a literal `$`. Secrets may also name one of the credential
+/// sources in `crate::credentials` (`file:`, `cmd:`, `git-credential`, `netrc`).
 #[derive(Debug, Clone, PartialEq)]
 pub struct EnvString(String);
 
@@ -16,22 +23,30 @@ impl EnvString {
     }
 
     /// Resolve the value, expanding environment variables if needed
-    /// Syntax: ${VAR_NAME} - reads from environment variable
+    /// Syntax: ${VAR_NAME} or ${VAR_NAME:-default} - reads from environment variable
     /// Plain text is returned as-is
     pub fn resolve(&self) -> Result<String, EnvResolutionError> {
-        let value = &self.0;
-        
-        // Check if this is an environment variable reference
-        if value.starts_with("${") && value.ends_with("}") {
-            let var_name = &value[2..value.len()-1];
-            env::var(var_name)
-                .map_err(|_| EnvResolutionError::VariableNotSet {
-                    var_name: var_name.to_string(),
-                })
-        } else {
-            // Return the literal value
-            Ok(value.clone())
-        }
+        interpolate(&self.0)
+    }
+
+    /// Resolve a secret (such as a token) for a provider host, and register
+    /// it for redaction. Only secrets are looked up in credential sources;
+    /// the host is used by `git-credential` and `netrc` when they don't name one.
+    pub fn resolve_secret(&self, host: Option<&str>) -> Result<String, EnvResolutionError> {
+        let value = interpolate(&self.0)?;
+        let secret = match CredentialSource::parse(&value) {
+            Some(source) => source.resolve(host)?,
+            None => value,
+        };
+        redact::register_secret(&secret);
+        Ok(secret)
+    }
+
+    /// Resolve the value as a filesystem path, additionally expanding a
+    /// leading `~` to the home directory
+    pub fn resolve_path(&self) -> Result<String, EnvResolutionError> {
+        let value = interpolate(&self.0)?;
+        Ok(expand_home(&value).to_string_lossy().into_owned())
     }
 
     /// Get the raw value without resolving
@@ -41,11 +56,125 @@ impl EnvString {
     }
 }
 
+impl From<&str> for EnvString {
+    fn from(value: &str) -> Self {
+        EnvString(value.to_string())
+    }
+}
+
+impl From<String> for EnvString {
+    fn from(value: String) -> Self {
+        EnvString(value)
+    }
+}
+
+/// Expand `${VAR}`, `${VAR:-default}` and `$$` in a string.
+/// A `$` not followed by `{` or `$` is kept literally.
+fn interpolate(value: &str) -> Result<String, EnvResolutionError> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(remaining) = after.strip_prefix('$') {
+            result.push('$');
+            rest = remaining;
+        } else if let Some(expression) = after.strip_prefix('{') {
+            let end =
+                matching_brace(expression).ok_or_else(|| EnvResolutionError::InvalidSyntax {
+                    value: value.to_string(),
+                    reason: "unclosed '${'".to_string(),
+                })?;
+            result.push_str(&expand_variable(&expression[..end], value)?);
+            rest = &expression[end + 1..];
+        } else {
+            result.push('$');
+            rest = after;
+        }
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Index of the `}` closing an expression, allowing `${...}` in defaults
+fn matching_brace(expression: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut previous = '\0';
+
+    for (index, c) in expression.char_indices() {
+        match c {
+            '{' if previous == '$' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+    }
+
+    None
+}
+
+fn expand_variable(expression: &str, whole: &str) -> Result<String, EnvResolutionError> {
+    let (var_name, default) = match expression.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (expression, None),
+    };
+
+    if var_name.is_empty()
+        || !var_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(EnvResolutionError::InvalidSyntax {
+            value: whole.to_string(),
+            reason: format!("invalid variable name '{}'", var_name),
+        });
+    }
+
+    match (env::var(var_name), default) {
+        (Ok(value), Some(_)) if !value.is_empty() => Ok(value),
+        (Ok(value), None) => Ok(value),
+        (_, Some(default)) => interpolate(default),
+        (Err(_), None) => Err(EnvResolutionError::VariableNotSet {
+            var_name: var_name.to_string(),
+        }),
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, thiserror::Error)]
 pub enum EnvResolutionError {
     #[error("Environment variable '{var_name}' is not set")]
     VariableNotSet { var_name: String },
+
+    #[error("Credential source '{source_name}' failed: {message}")]
+    SourceFailed {
+        source_name: String,
+        message: String,
+    },
+
+    #[error("Invalid variable reference in '{value}': {reason}")]
+    InvalidSyntax { value: String, reason: String },
+
+    #[error("{key}: {error}")]
+    ForKey {
+        key: String,
+        #[source]
+        error: Box<EnvResolutionError>,
+    },
+}
+
+impl EnvResolutionError {
+    /// Attach the config key (e.g. `providers.gitlab.token`) the error came from
+    pub fn for_key(self, key: impl Into<String>) -> Self {
+        EnvResolutionError::ForKey {
+            key: key.into(),
+            error: Box::new(self),
+        }
+    }
 }
 
 // Custom serializer for EnvString
@@ -86,6 +215,28 @@ impl<'de> Deserialize<'de> for EnvString {
             {
                 Ok(EnvString(value))
             }
+
+            // Other scalars are taken as written, e.g. `commit.gpgsign: true`
+            fn visit_bool<E>(self, value: bool) -> Result<EnvString, E>
+            where
+                E: de::Error,
+            {
+                Ok(EnvString(value.to_string()))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<EnvString, E>
+            where
+                E: de::Error,
+            {
+                Ok(EnvString(value.to_string()))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<EnvString, E>
+            where
+                E: de::Error,
+            {
+                Ok(EnvString(value.to_string()))
+            }
         }
 
         deserializer.deserialize_string(EnvStringVisitor)
@@ -95,6 +246,7 @@ impl<'de> Deserialize<'de> for EnvString {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
 
     #[test]
     fn test_literal_string() {
@@ -117,6 +269,74 @@ mod tests {
         assert!(env_str.resolve().is_err());
     }
 
+    #[test]
+    fn test_interpolation_inside_string() {
+        env::set_var("TEST_INTERP_HOST", "gitlab.internal");
+        let env_str = EnvString::new("https://${TEST_INTERP_HOST}/api".to_string());
+        assert_eq!(env_str.resolve().unwrap(), "https://gitlab.internal/api");
+        env::remove_var("TEST_INTERP_HOST");
+    }
+
+    #[test]
+    fn test_interpolation_default_value() {
+        env::remove_var("TEST_INTERP_UNSET");
+        env::set_var("TEST_INTERP_EMPTY", "");
+        env::set_var("TEST_INTERP_FALLBACK", "nested");
+
+        let unset = EnvString::new("${TEST_INTERP_UNSET:-fallback}".to_string());
+        assert_eq!(unset.resolve().unwrap(), "fallback");
+
+        let empty = EnvString::new("${TEST_INTERP_EMPTY:-fallback}".to_string());
+        assert_eq!(empty.resolve().unwrap(), "fallback");
+
+        let nested = EnvString::new("${TEST_INTERP_UNSET:-${TEST_INTERP_FALLBACK}}".to_string());
+        assert_eq!(nested.resolve().unwrap(), "nested");
+
+        env::remove_var("TEST_INTERP_EMPTY");
+        env::remove_var("TEST_INTERP_FALLBACK");
+    }
+
+    #[test]
+    fn test_interpolation_escapes_and_literal_dollars() {
+        let env_str = EnvString::new("pa$$word-${NOT_A_VAR_ESCAPE:-x}-$5".to_string());
+        assert_eq!(env_str.resolve().unwrap(), "pa$word-x-$5");
+
+        let escaped = EnvString::new("$${HOME}".to_string());
+        assert_eq!(escaped.resolve().unwrap(), "${HOME}");
+    }
+
+    #[test]
+    fn test_interpolation_rejects_unclosed_reference() {
+        let env_str = EnvString::new("${UNCLOSED".to_string());
+        assert!(matches!(
+            env_str.resolve(),
+            Err(EnvResolutionError::InvalidSyntax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_path_expands_home() {
+        let home = crate::credentials::home_dir().unwrap();
+        let env_str = EnvString::new("~/src/team".to_string());
+        assert_eq!(
+            PathBuf::from(env_str.resolve_path().unwrap()),
+            home.join("src/team")
+        );
+    }
+
+    #[test]
+    fn test_error_names_config_key() {
+        env::remove_var("TEST_KEYED_MISSING");
+        let error = EnvString::new("${TEST_KEYED_MISSING}".to_string())
+            .resolve()
+            .unwrap_err()
+            .for_key("groups.gitlab[0].local_dir");
+
+        let message = error.to_string();
+        assert!(message.contains("groups.gitlab[0].local_dir"));
+        assert!(message.contains("TEST_KEYED_MISSING"));
+    }
+
     #[test]
     fn test_raw_value() {
         let env_str = EnvString::new("${MY_VAR}".to_string());
@@ -150,6 +370,140 @@ pub struct RangerConfig {
     
     #[serde(default)]
     pub repos: Vec<RepoConfig>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub url_rewrites: Vec<UrlRewrite>,
+
+    /// Default for the whole workspace; groups may override it
+    #[serde(default, skip_serializing_if = "SyncMode::is_checkout")]
+    pub mode: SyncMode,
+
+    /// Workspace hooks: `pre_sync`/`post_sync` run once around the whole
+    /// sync, `post_clone`/`post_fetch` run for every repo
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+
+    /// What sync does with checkouts that aren't clean; groups and repos may override it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<UpdatePolicy>,
+
+    /// Switch clean checkouts still on the old default branch when the
+    /// upstream default changes; groups and repos may override it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_default_branch: Option<bool>,
+}
+
+/// Shell commands run around sync. Each hook takes one command or a list.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct HooksConfig {
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
+    pub pre_sync: Vec<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
+    pub post_sync: Vec<String>,
+
+    /// Run in the repo after it was cloned, e.g. `make setup`
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
+    pub post_clone: Vec<String>,
+
+    /// Run in the repo after it was fetched
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
+    pub post_fetch: Vec<String>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == HooksConfig::default()
+    }
+
+    /// These hooks followed by `more`
+    pub fn chain(&self, more: &HooksConfig) -> HooksConfig {
+        let join = |a: &[String], b: &[String]| [a, b].concat();
+
+        HooksConfig {
+            pre_sync: join(&self.pre_sync, &more.pre_sync),
+            post_sync: join(&self.post_sync, &more.post_sync),
+            post_clone: join(&self.post_clone, &more.post_clone),
+            post_fetch: join(&self.post_fetch, &more.post_fetch),
+        }
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(command) => vec![command],
+        OneOrMany::Many(commands) => commands,
+    })
+}
+
+/// Whether repos are kept as working trees or as bare mirrors
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncMode {
+    /// Regular clones with a working tree
+    #[default]
+    Checkout,
+    /// Bare `git clone --mirror` copies of every ref, for backups
+    Mirror,
+}
+
+impl SyncMode {
+    pub fn is_checkout(&self) -> bool {
+        *self == SyncMode::Checkout
+    }
+
+    /// Directory name for a repo: mirrors get the usual `.git` suffix of
+    /// bare repositories, so the layout can serve as a clone source
+    pub fn repo_dir_name(&self, name: &str) -> String {
+        match self {
+            SyncMode::Checkout => name.to_string(),
+            SyncMode::Mirror => format!("{}.git", name),
+        }
+    }
+
+    /// Whether a repo has been cloned at `path`
+    pub fn is_cloned(&self, path: &Path) -> bool {
+        match self {
+            SyncMode::Checkout => path.join(".git").exists(),
+            SyncMode::Mirror => path.join("HEAD").exists(),
+        }
+    }
+}
+
+/// Rewrite rule for clone URLs, like git's `url.<to>.insteadOf <from>`
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct UrlRewrite {
+    /// Prefix to match, e.g. `git@gitlab.example.com:`
+    pub from: String,
+
+    /// Replacement for the prefix, e.g. `ssh://git@gitlab.example.com:2222/`
+    pub to: String,
 }
 
 /// Provider configurations
@@ -162,14 +516,185 @@ pub struct Providers {
 /// GitLab provider configuration
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct GitLabProvider {
-    pub host: String,
+    pub host: EnvString,
     pub token: EnvString,
+
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    #[serde(default, skip_serializing_if = "CloneConfig::is_empty")]
+    pub clone: CloneConfig,
+
+    #[serde(flatten)]
+    pub connection: ConnectionConfig,
 }
 
 /// GitHub provider configuration
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct GitHubProvider {
     pub token: EnvString,
+
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    #[serde(default, skip_serializing_if = "CloneConfig::is_empty")]
+    pub clone: CloneConfig,
+
+    #[serde(flatten)]
+    pub connection: ConnectionConfig,
+}
+
+/// Network settings for a provider, applied both to API requests and to
+/// the git processes that clone and fetch its repositories
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct ConnectionConfig {
+    /// PEM bundle of extra CA certificates to trust
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<EnvString>,
+
+    /// PEM client certificate for mutual TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<EnvString>,
+
+    /// PEM (PKCS#8) private key for `client_cert`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<EnvString>,
+
+    /// Proxy URL, e.g. `http://proxy.corp:3128`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<EnvString>,
+
+    /// Comma-separated hosts that bypass the proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+
+    /// Request timeout in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// Default API request timeout when a provider doesn't set one
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+impl ConnectionConfig {
+    /// Resolve variables and make certificate paths absolute.
+    /// `key_prefix` (e.g. `providers.gitlab`) is used in error messages.
+    pub fn resolve(
+        &self,
+        base_dir: &Path,
+        key_prefix: &str,
+    ) -> Result<ConnectionSettings, EnvResolutionError> {
+        let path =
+            |value: &Option<EnvString>, key: &str| -> Result<Option<PathBuf>, EnvResolutionError> {
+                match value {
+                    Some(value) => {
+                        let resolved = value
+                            .resolve_path()
+                            .map_err(|e| e.for_key(format!("{}.{}", key_prefix, key)))?;
+                        Ok(Some(base_dir.join(resolved)))
+                    }
+                    None => Ok(None),
+                }
+            };
+
+        let proxy = match &self.proxy {
+            Some(proxy) => Some(
+                proxy
+                    .resolve()
+                    .map_err(|e| e.for_key(format!("{}.proxy", key_prefix)))?,
+            ),
+            None => None,
+        };
+
+        Ok(ConnectionSettings {
+            ca_cert: path(&self.ca_cert, "ca_cert")?,
+            client_cert: path(&self.client_cert, "client_cert")?,
+            client_key: path(&self.client_key, "client_key")?,
+            proxy,
+            no_proxy: self.no_proxy.clone(),
+            timeout: self.timeout.map(Duration::from_secs),
+        })
+    }
+}
+
+/// Resolved `ConnectionConfig`, ready to apply to HTTP clients and git
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConnectionSettings {
+    pub ca_cert: Option<PathBuf>,
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    pub timeout: Option<Duration>,
+}
+
+impl ConnectionSettings {
+    /// `-c key=value` settings that make git behave like the API client
+    pub fn git_config(&self) -> Vec<(String, String)> {
+        let mut config = Vec::new();
+
+        if let Some(ref ca_cert) = self.ca_cert {
+            config.push(("http.sslCAInfo".to_string(), ca_cert.display().to_string()));
+        }
+        if let Some(ref client_cert) = self.client_cert {
+            config.push((
+                "http.sslCert".to_string(),
+                client_cert.display().to_string(),
+            ));
+        }
+        if let Some(ref client_key) = self.client_key {
+            config.push(("http.sslKey".to_string(), client_key.display().to_string()));
+        }
+        if let Some(ref proxy) = self.proxy {
+            config.push(("http.proxy".to_string(), proxy.clone()));
+        }
+        if let Some(timeout) = self.timeout {
+            // git has no overall timeout; abort transfers stalled for that long
+            config.push(("http.lowSpeedLimit".to_string(), "1".to_string()));
+            config.push((
+                "http.lowSpeedTime".to_string(),
+                timeout.as_secs().to_string(),
+            ));
+        }
+
+        config
+    }
+
+    /// Environment variables for git; curl reads `no_proxy` from the environment
+    pub fn git_env(&self) -> Vec<(String, String)> {
+        match self.no_proxy {
+            Some(ref no_proxy) => vec![
+                ("no_proxy".to_string(), no_proxy.clone()),
+                ("NO_PROXY".to_string(), no_proxy.clone()),
+            ],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Retry policy for provider API requests
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Number of retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+
+    /// Backoff before the first retry, doubled on each further retry
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound for the computed backoff, and the longest wait a server
+    /// may ask for before requests fail instead
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 60_000,
+        }
+    }
 }
 
 /// Group configurations by provider
@@ -188,27 +713,275 @@ pub struct GroupConfig {
     pub name: String,
     
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub local_dir: Option<String>,
+    pub local_dir: Option<EnvString>,
     
     #[serde(default)]
     pub recursive: bool,
+
+    #[serde(default, skip_serializing_if = "CloneConfig::is_empty")]
+    pub clone: CloneConfig,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submodules: Option<SubmoduleMode>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lfs: Option<LfsMode>,
+
+    /// Overrides the workspace `mode` for this group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SyncMode>,
+
+    /// Also mirror project wikis (mirror mode only)
+    #[serde(default)]
+    pub wikis: bool,
+
+    /// Second GitLab instance every repo of the group is pushed to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_to: Option<PushTarget>,
+
+    /// Settings written to each repo's `.git/config`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_config: BTreeMap<String, EnvString>,
+
+    /// Hooks run for each repo of the group, after the workspace ones
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+
+    /// Overrides the workspace `update_policy` for this group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<UpdatePolicy>,
+
+    /// Overrides the workspace `follow_default_branch` for this group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_default_branch: Option<bool>,
 }
 
-/// Configuration for an individual repository
+/// Where a group's repos are mirror-pushed after each sync.
+/// Missing projects are created through the target's API.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PushTarget {
+    /// Forge the target runs
+    #[serde(default)]
+    pub provider: PushProvider,
+
+    pub host: EnvString,
+    pub token: EnvString,
+
+    /// Group or user namespace on the target taking the place of the source group
+    pub namespace: String,
+
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    #[serde(flatten)]
+    pub connection: ConnectionConfig,
+}
+
+/// Forges a group can be mirror-pushed to
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PushProvider {
+    #[default]
+    GitLab,
+}
+
+/// Configuration for an individual repository
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 pub struct RepoConfig {
     pub url: String,
     
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub local_dir: Option<String>,
+    pub local_dir: Option<EnvString>,
+
+    #[serde(default, skip_serializing_if = "CloneConfig::is_empty")]
+    pub clone: CloneConfig,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submodules: Option<SubmoduleMode>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lfs: Option<LfsMode>,
+
+    /// Extra remotes by name, besides `origin`. For forks discovered through
+    /// a provider, `upstream` defaults to the parent project.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, String>,
+
+    /// Settings written to the repo's `.git/config`, e.g. `user.email`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_config: BTreeMap<String, EnvString>,
+
+    /// Hooks run for this repo, after the workspace (and group) ones
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+
+    /// Overrides the workspace (or group) `update_policy` for this repo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<UpdatePolicy>,
+
+    /// Overrides the workspace (or group) `follow_default_branch` for this repo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_default_branch: Option<bool>,
+}
+
+/// What sync does with an existing checkout that isn't clean: one with
+/// uncommitted changes, a detached HEAD, an unfinished rebase or merge,
+/// no upstream branch or history diverged from upstream. Clean checkouts
+/// are always fetched and fast-forwarded.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdatePolicy {
+    /// Leave the repo alone, not even fetching
+    Skip,
+    /// Stash uncommitted changes around the fast-forward; other states
+    /// are handled like `fetch-only`
+    Autostash,
+    /// Fetch, but leave the working tree and current branch alone
+    #[default]
+    FetchOnly,
+}
+
+impl UpdatePolicy {
+    /// Name as written in the config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdatePolicy::Skip => "skip",
+            UpdatePolicy::Autostash => "autostash",
+            UpdatePolicy::FetchOnly => "fetch-only",
+        }
+    }
+}
+
+/// Which submodules sync checks out
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmoduleMode {
+    /// Leave submodules alone
+    None,
+    /// Check out top-level submodules
+    Init,
+    /// Check out submodules and their nested submodules
+    Recursive,
+}
+
+/// What sync does with Git LFS objects
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LfsMode {
+    /// Leave LFS files as pointers
+    Skip,
+    /// Download LFS objects without checking them out
+    Fetch,
+    /// Download LFS objects and check them out
+    Pull,
+}
+
+/// How repositories are cloned. Set at provider, group or repo level;
+/// each field left unset is inherited from the level above.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct CloneConfig {
+    /// Shallow clone with this many commits of history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+
+    /// Partial clone filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<CloneFilter>,
+
+    /// Only fetch the default branch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_branch: Option<bool>,
+
+    /// Directories to check out (cone-mode sparse checkout)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse: Option<Vec<String>>,
+}
+
+/// Partial clone filters supported by `clone.filter`
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum CloneFilter {
+    /// Download blobs on demand
+    #[serde(rename = "blob:none")]
+    BlobNone,
+
+    /// Download trees and blobs on demand
+    #[serde(rename = "tree:0")]
+    TreeZero,
+}
+
+impl CloneFilter {
+    /// Value for `git clone --filter`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloneFilter::BlobNone => "blob:none",
+            CloneFilter::TreeZero => "tree:0",
+        }
+    }
+}
+
+impl CloneConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == CloneConfig::default()
+    }
+
+    /// These options with any field set in `overrides` taking precedence
+    pub fn merged(&self, overrides: &CloneConfig) -> CloneConfig {
+        CloneConfig {
+            depth: overrides.depth.or(self.depth),
+            filter: overrides.filter.or(self.filter),
+            single_branch: overrides.single_branch.or(self.single_branch),
+            sparse: overrides.sparse.clone().or_else(|| self.sparse.clone()),
+        }
+    }
+
+    /// Extra arguments for `git clone`
+    pub fn clone_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(depth) = self.depth {
+            args.push(format!("--depth={}", depth));
+        }
+        if let Some(filter) = self.filter {
+            args.push(format!("--filter={}", filter.as_str()));
+        }
+        match self.single_branch {
+            Some(true) => args.push("--single-branch".to_string()),
+            // --depth implies --single-branch unless told otherwise
+            Some(false) => args.push("--no-single-branch".to_string()),
+            None => {}
+        }
+        if self.sparse.is_some() {
+            args.push("--sparse".to_string());
+        }
+
+        args
+    }
+}
+
+impl RepoConfig {
+    /// Resolve `git_config` values; `key_prefix` (e.g. `repos[0]`) is used
+    /// in error messages
+    pub fn resolve_git_config(
+        &self,
+        key_prefix: &str,
+    ) -> Result<Vec<(String, String)>, EnvResolutionError> {
+        self.git_config
+            .iter()
+            .map(|(key, value)| {
+                value
+                    .resolve()
+                    .map(|value| (key.clone(), value))
+                    .map_err(|e| e.for_key(format!("{}.git_config.{}", key_prefix, key)))
+            })
+            .collect()
+    }
 }
 
 impl RangerConfig {
     /// Load configuration from a YAML file
     pub fn load_from_file(path: &std::path::Path) -> Result<Self, ConfigLoadError> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| ConfigLoadError::IoError(e))?;
-        
+        let content = std::fs::read_to_string(path).map_err(ConfigLoadError::IoError)?;
+
         let config: RangerConfig = serde_yaml::from_str(&content)
             .map_err(|e| ConfigLoadError::ParseError(e.to_string()))?;
         
@@ -220,15 +993,36 @@ impl RangerConfig {
         &self.repos
     }
     
+    /// Apply `url_rewrites` to a clone URL. As with git's `insteadOf`,
+    /// the longest matching prefix wins and only one rule is applied.
+    pub fn rewrite_url(&self, url: &str) -> String {
+        self.url_rewrites
+            .iter()
+            .filter(|rule| !rule.from.is_empty() && url.starts_with(&rule.from))
+            .max_by_key(|rule| rule.from.len())
+            .map(|rule| format!("{}{}", rule.to, &url[rule.from.len()..]))
+            .unwrap_or_else(|| url.to_string())
+    }
+
     /// Validate that required environment variables for providers are set
     #[allow(dead_code)]
     pub fn validate_providers(&self) -> Result<(), EnvResolutionError> {
         if let Some(ref gitlab) = self.providers.gitlab {
-            gitlab.token.resolve()?;
+            let host = gitlab
+                .host
+                .resolve()
+                .map_err(|e| e.for_key("providers.gitlab.host"))?;
+            gitlab
+                .token
+                .resolve_secret(Some(&host))
+                .map_err(|e| e.for_key("providers.gitlab.token"))?;
         }
         
         if let Some(ref github) = self.providers.github {
-            github.token.resolve()?;
+            github
+                .token
+                .resolve_secret(Some("https://github.com"))
+                .map_err(|e| e.for_key("providers.github.token"))?;
         }
         
         Ok(())
@@ -308,7 +1102,191 @@ groups:
         
         let config: RangerConfig = serde_yaml::from_str(yaml).unwrap();
         
-        assert_eq!(config.groups.gitlab[0].recursive, false);
+        assert!(!config.groups.gitlab[0].recursive);
+    }
+
+    #[test]
+    fn test_connection_settings_resolve_and_map_to_git_config() {
+        let yaml = r#"
+providers:
+  gitlab:
+    host: "https://gitlab.example.com"
+    token: "${GITLAB_TOKEN}"
+    ca_cert: "certs/corp-ca.pem"
+    proxy: "http://proxy.corp:3128"
+    no_proxy: "localhost,.corp"
+    timeout: 90
+"#;
+
+        let config: RangerConfig = serde_yaml::from_str(yaml).unwrap();
+        let connection = config.providers.gitlab.unwrap().connection;
+        let settings = connection
+            .resolve(Path::new("/workspace"), "providers.gitlab")
+            .unwrap();
+
+        assert_eq!(
+            settings.ca_cert,
+            Some(PathBuf::from("/workspace/certs/corp-ca.pem"))
+        );
+        assert_eq!(settings.timeout, Some(Duration::from_secs(90)));
+
+        let git_config = settings.git_config();
+        assert!(git_config.contains(&(
+            "http.sslCAInfo".to_string(),
+            "/workspace/certs/corp-ca.pem".to_string()
+        )));
+        assert!(git_config.contains(&(
+            "http.proxy".to_string(),
+            "http://proxy.corp:3128".to_string()
+        )));
+        assert!(settings
+            .git_env()
+            .contains(&("NO_PROXY".to_string(), "localhost,.corp".to_string())));
+    }
+
+    #[test]
+    fn test_retry_config_defaults_and_overrides() {
+        let yaml = r#"
+providers:
+  gitlab:
+    host: "https://gitlab.example.com"
+    token: "${GITLAB_TOKEN}"
+    retry:
+      max_retries: 2
+"#;
+
+        let config: RangerConfig = serde_yaml::from_str(yaml).unwrap();
+        let retry = &config.providers.gitlab.unwrap().retry;
+
+        assert_eq!(retry.max_retries, 2);
+        assert_eq!(
+            retry.initial_backoff_ms,
+            RetryConfig::default().initial_backoff_ms
+        );
+    }
+
+    #[test]
+    fn test_clone_config_inherits_unset_fields() {
+        let yaml = r#"
+providers:
+  gitlab:
+    host: "https://gitlab.example.com"
+    token: "${GITLAB_TOKEN}"
+    clone:
+      depth: 1
+      filter: "blob:none"
+
+groups:
+  gitlab:
+    - name: "monorepos"
+      clone:
+        depth: 50
+        sparse: ["services/api"]
+"#;
+
+        let config: RangerConfig = serde_yaml::from_str(yaml).unwrap();
+        let provider = &config.providers.gitlab.as_ref().unwrap().clone;
+        let clone = provider.merged(&config.groups.gitlab[0].clone);
+
+        assert_eq!(clone.depth, Some(50));
+        assert_eq!(clone.filter, Some(CloneFilter::BlobNone));
+        assert_eq!(
+            clone.clone_args(),
+            vec!["--depth=50", "--filter=blob:none", "--sparse"]
+        );
+    }
+
+    #[test]
+    fn test_clone_config_rejects_unknown_filter() {
+        let yaml = r#"
+repos:
+  - url: "https://github.com/example/repo.git"
+    clone:
+      filter: "blob:limit=1m"
+"#;
+
+        assert!(serde_yaml::from_str::<RangerConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_update_policy_levels() {
+        let yaml = r#"
+update_policy: skip
+groups:
+  gitlab:
+    - name: "team"
+      update_policy: autostash
+repos:
+  - url: "https://github.com/example/repo.git"
+    update_policy: fetch-only
+  - url: "https://github.com/example/other.git"
+"#;
+
+        let config: RangerConfig = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(config.update_policy, Some(UpdatePolicy::Skip));
+        assert_eq!(
+            config.groups.gitlab[0].update_policy,
+            Some(UpdatePolicy::Autostash)
+        );
+        assert_eq!(config.repos[0].update_policy, Some(UpdatePolicy::FetchOnly));
+        assert_eq!(config.repos[1].update_policy, None);
+        assert_eq!(UpdatePolicy::default(), UpdatePolicy::FetchOnly);
+    }
+
+    #[test]
+    fn test_push_target_provider_defaults_to_gitlab() {
+        let yaml = |provider: &str| {
+            format!(
+                r#"
+groups:
+  gitlab:
+    - name: "team"
+      push_to:{}
+        host: "https://gitlab.new.example"
+        token: "${{NEW_TOKEN}}"
+        namespace: "team"
+"#,
+                provider
+            )
+        };
+
+        let config: RangerConfig = serde_yaml::from_str(&yaml("")).unwrap();
+        let target = config.groups.gitlab[0].push_to.as_ref().unwrap();
+        assert_eq!(target.provider, PushProvider::GitLab);
+
+        let config: RangerConfig =
+            serde_yaml::from_str(&yaml("\n        provider: gitlab")).unwrap();
+        let target = config.groups.gitlab[0].push_to.as_ref().unwrap();
+        assert_eq!(target.provider, PushProvider::GitLab);
+
+        assert!(serde_yaml::from_str::<RangerConfig>(&yaml("\n        provider: gitea")).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_url_uses_longest_matching_prefix() {
+        let yaml = r#"
+url_rewrites:
+  - from: "git@gitlab.example.com:"
+    to: "ssh://git@gitlab.example.com:2222/"
+  - from: "git@gitlab.example.com:mirrored/"
+    to: "https://mirror.internal/mirrored/"
+"#;
+
+        let config: RangerConfig = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            config.rewrite_url("git@gitlab.example.com:team/app.git"),
+            "ssh://git@gitlab.example.com:2222/team/app.git"
+        );
+        assert_eq!(
+            config.rewrite_url("git@gitlab.example.com:mirrored/app.git"),
+            "https://mirror.internal/mirrored/app.git"
+        );
+        assert_eq!(
+            config.rewrite_url("https://github.com/example/app.git"),
+            "https://github.com/example/app.git"
+        );
     }
     
     #[test]
@@ -0,0 +1,491 @@
+//! In-place edits of ranger.yaml that keep the user's comments and layout.
+//!
+//! A serde round trip would drop comments and reorder keys, so edits work on
+//! the text of the block they change and leave every other line untouched.
+//! The result is parsed again before it is returned.
+
+use crate::config::RangerConfig;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigEditError {
+    #[error("Cannot edit {0} automatically: {1}")]
+    Unsupported(String, String),
+
+    #[error("Edited configuration would not parse: {0}")]
+    InvalidResult(String),
+}
+
+/// A standalone `repos` entry to add
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewRepo {
+    pub url: String,
+    pub local_dir: Option<String>,
+}
+
+/// Append entries to the top-level `repos` list, creating it if needed
+pub fn add_repos(content: &str, repos: &[NewRepo]) -> Result<String, ConfigEditError> {
+    let items: Vec<Vec<String>> = repos
+        .iter()
+        .map(|repo| {
+            let mut fields = vec![format!("url: {}", quote(&repo.url))];
+            if let Some(ref local_dir) = repo.local_dir {
+                fields.push(format!("local_dir: {}", quote(local_dir)));
+            }
+            fields
+        })
+        .collect();
+
+    append_list_items(content, &["repos"], &items)
+}
+
+/// A `groups.<provider>` entry to add
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewGroup {
+    pub name: String,
+    pub local_dir: Option<String>,
+    pub recursive: bool,
+}
+
+/// Append entries to `groups.<provider>`, creating the keys if needed
+pub fn add_groups(
+    content: &str,
+    provider: &str,
+    groups: &[NewGroup],
+) -> Result<String, ConfigEditError> {
+    let items: Vec<Vec<String>> = groups
+        .iter()
+        .map(|group| {
+            let mut fields = vec![format!("name: {}", quote(&group.name))];
+            if let Some(ref local_dir) = group.local_dir {
+                fields.push(format!("local_dir: {}", quote(local_dir)));
+            }
+            if group.recursive {
+                fields.push("recursive: true".to_string());
+            }
+            fields
+        })
+        .collect();
+
+    append_list_items(content, &["groups", provider], &items)
+}
+
+/// Remove the `index`-th entry of the top-level `repos` list, together with
+/// the comment lines right above it
+pub fn remove_repo(content: &str, index: usize) -> Result<String, ConfigEditError> {
+    remove_list_item(content, &["repos"], index)
+}
+
+/// Double-quoted YAML scalar
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Append items to the block sequence at `path` (e.g. `["groups", "gitlab"]`).
+/// Each item is a list of `key: value` lines.
+fn append_list_items(
+    content: &str,
+    path: &[&str],
+    items: &[Vec<String>],
+) -> Result<String, ConfigEditError> {
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let key_name = path.join(".");
+
+    let (key_line, key_indent) = ensure_key(&mut lines, path)?;
+
+    // `repos: []` becomes a block list; other inline values can't be extended
+    let (value, comment) = split_inline_value(&lines[key_line]);
+    match value.as_str() {
+        "" => {}
+        "[]" => {
+            let key = &path[path.len() - 1];
+            lines[key_line] = format!("{}{}:{}", " ".repeat(key_indent), key, comment);
+        }
+        _ => {
+            return Err(ConfigEditError::Unsupported(
+                key_name,
+                "it is not a block list".to_string(),
+            ))
+        }
+    }
+
+    let block_end = block_end(&lines, key_line, key_indent);
+    let item_indent = (key_line + 1..block_end)
+        .map(|i| &lines[i])
+        .find(|line| line.trim_start().starts_with('-'))
+        .map(|line| indentation(line))
+        .unwrap_or(key_indent + 2);
+
+    let mut rendered = Vec::new();
+    for item in items {
+        for (index, field) in item.iter().enumerate() {
+            let marker = if index == 0 { "- " } else { "  " };
+            rendered.push(format!("{}{}{}", " ".repeat(item_indent), marker, field));
+        }
+    }
+    lines.splice(block_end..block_end, rendered);
+
+    finish(lines, content)
+}
+
+fn remove_list_item(content: &str, path: &[&str], index: usize) -> Result<String, ConfigEditError> {
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let key_name = path.join(".");
+
+    let (key_line, key_indent) = locate_key(&lines, path).ok_or_else(|| {
+        ConfigEditError::Unsupported(key_name.clone(), "key not found".to_string())
+    })?;
+    if !split_inline_value(&lines[key_line]).0.is_empty() {
+        return Err(ConfigEditError::Unsupported(
+            key_name,
+            "it is not a block list".to_string(),
+        ));
+    }
+
+    let block_end = block_end(&lines, key_line, key_indent);
+    let item_indent = (key_line + 1..block_end)
+        .map(|i| &lines[i])
+        .find(|line| is_content(line))
+        .map(|line| indentation(line));
+    let starts: Vec<usize> = (key_line + 1..block_end)
+        .filter(|&i| {
+            Some(indentation(&lines[i])) == item_indent && lines[i].trim_start().starts_with('-')
+        })
+        .collect();
+
+    // Comments directly above an item describe it
+    let span_start = |start: usize| {
+        let mut first = start;
+        while first > key_line + 1 && lines[first - 1].trim_start().starts_with('#') {
+            first -= 1;
+        }
+        first
+    };
+
+    let start = *starts.get(index).ok_or_else(|| {
+        ConfigEditError::Unsupported(key_name.clone(), format!("it has no entry {}", index))
+    })?;
+    let end = starts
+        .get(index + 1)
+        .map(|&next| span_start(next))
+        .unwrap_or(block_end);
+    lines.drain(span_start(start)..end);
+
+    // An empty block list would be null rather than an empty list
+    if starts.len() == 1 {
+        let (_, comment) = split_inline_value(&lines[key_line]);
+        let key = &path[path.len() - 1];
+        lines[key_line] = format!("{}{}: []{}", " ".repeat(key_indent), key, comment);
+    }
+
+    finish(lines, content)
+}
+
+/// Line and indentation of the key at `path`, if present
+fn locate_key(lines: &[String], path: &[&str]) -> Option<(usize, usize)> {
+    let mut range = (0, lines.len());
+    let mut indent = 0;
+    let mut found = None;
+
+    for key in path {
+        let line = find_key(lines, range, indent, key)?;
+        found = Some((line, indent));
+        let end = block_end(lines, line, indent);
+        range = (line + 1, end);
+        indent = (line + 1..end)
+            .map(|i| &lines[i])
+            .find(|l| is_content(l))
+            .map(|l| indentation(l))
+            .filter(|child| *child > indent)?;
+    }
+
+    found
+}
+
+/// Find the key at `path`, adding missing levels at the end of their parent.
+/// Returns its line index and indentation.
+fn ensure_key(lines: &mut Vec<String>, path: &[&str]) -> Result<(usize, usize), ConfigEditError> {
+    let mut range = (0, lines.len());
+    let mut indent = 0;
+    let mut found = None;
+
+    for (depth, key) in path.iter().enumerate() {
+        let line = match find_key(lines, range, indent, key) {
+            Some(line) => line,
+            None => {
+                let at = match found {
+                    Some((parent, parent_indent)) => block_end(lines, parent, parent_indent),
+                    None => content_end(lines),
+                };
+                // Keep a blank line before new top-level keys
+                let mut new_lines = Vec::new();
+                if depth == 0 && at > 0 && !lines[at - 1].trim().is_empty() {
+                    new_lines.push(String::new());
+                }
+                new_lines.push(format!("{}{}:", " ".repeat(indent), key));
+                let line = at + new_lines.len() - 1;
+                lines.splice(at..at, new_lines);
+                line
+            }
+        };
+
+        let (value, _) = split_inline_value(&lines[line]);
+        if depth + 1 < path.len() && !value.is_empty() {
+            return Err(ConfigEditError::Unsupported(
+                path[..=depth].join("."),
+                "it is not a block mapping".to_string(),
+            ));
+        }
+
+        found = Some((line, indent));
+        let end = block_end(lines, line, indent);
+        range = (line + 1, end);
+        indent = (line + 1..end)
+            .map(|i| &lines[i])
+            .find(|l| is_content(l))
+            .map(|l| indentation(l))
+            .filter(|child| *child > indent)
+            .unwrap_or(indent + 2);
+    }
+
+    found.ok_or_else(|| ConfigEditError::Unsupported(path.join("."), "empty path".to_string()))
+}
+
+/// Line of `key:` at exactly `indent` within `range`
+fn find_key(lines: &[String], range: (usize, usize), indent: usize, key: &str) -> Option<usize> {
+    (range.0..range.1).find(|&i| {
+        let line = &lines[i];
+        indentation(line) == indent
+            && line
+                .trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with(':'))
+    })
+}
+
+/// End (exclusive) of the block started by the key at `key_line`: the line
+/// after its last content line. Comments following the block at or left
+/// of the key's indentation belong to whatever comes next.
+fn block_end(lines: &[String], key_line: usize, key_indent: usize) -> usize {
+    let mut end = key_line + 1;
+
+    for (i, line) in lines.iter().enumerate().skip(key_line + 1) {
+        let trimmed = line.trim_start();
+        let indent = indentation(line);
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            if !trimmed.is_empty() && indent > key_indent {
+                end = i + 1;
+            }
+            continue;
+        }
+
+        // Sequences may sit at the same indentation as their key
+        if indent > key_indent || (indent == key_indent && trimmed.starts_with('-')) {
+            end = i + 1;
+        } else {
+            break;
+        }
+    }
+
+    end
+}
+
+/// Index after the last non-blank line
+fn content_end(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Value after `key:` and any trailing comment (with its leading space)
+fn split_inline_value(line: &str) -> (String, String) {
+    let after_key = line.split_once(':').map(|(_, rest)| rest).unwrap_or("");
+    match after_key.find(" #") {
+        Some(pos) => (
+            after_key[..pos].trim().to_string(),
+            after_key[pos..].to_string(),
+        ),
+        None if after_key.trim_start().starts_with('#') => (String::new(), after_key.to_string()),
+        None => (after_key.trim().to_string(), String::new()),
+    }
+}
+
+/// Join edited lines with the original's line ending, keeping its final
+/// newline (or lack of one)
+fn finish(lines: Vec<String>, original: &str) -> Result<String, ConfigEditError> {
+    let newline = match original.find('\n') {
+        Some(pos) if original[..pos].ends_with('\r') => "\r\n",
+        _ => "\n",
+    };
+    let mut result = lines.join(newline);
+    if original.ends_with('\n') || original.is_empty() {
+        result.push_str(newline);
+    }
+
+    serde_yaml::from_str::<RangerConfig>(&result)
+        .map_err(|e| ConfigEditError::InvalidResult(e.to_string()))?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(url: &str, local_dir: Option<&str>) -> NewRepo {
+        NewRepo {
+            url: url.to_string(),
+            local_dir: local_dir.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_add_repos_keeps_comments_and_following_keys() {
+        let content = "# My workspace\n\
+                       repos:\n\
+                       \x20 # personal projects\n\
+                       \x20 - url: \"git@github.com:me/dotfiles.git\"  # keep me\n\
+                       \x20   local_dir: \"personal\"\n\
+                       \n\
+                       # Rewrites for the VPN\n\
+                       url_rewrites: []\n";
+
+        let result = add_repos(
+            content,
+            &[repo("git@github.com:me/notes.git", Some("personal"))],
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            "# My workspace\n\
+             repos:\n\
+             \x20 # personal projects\n\
+             \x20 - url: \"git@github.com:me/dotfiles.git\"  # keep me\n\
+             \x20   local_dir: \"personal\"\n\
+             \x20 - url: \"git@github.com:me/notes.git\"\n\
+             \x20   local_dir: \"personal\"\n\
+             \n\
+             # Rewrites for the VPN\n\
+             url_rewrites: []\n"
+        );
+    }
+
+    #[test]
+    fn test_add_repos_creates_or_expands_the_list() {
+        let result = add_repos("providers: {}\n", &[repo("https://x/a.git", None)]).unwrap();
+        assert_eq!(
+            result,
+            "providers: {}\n\nrepos:\n  - url: \"https://x/a.git\"\n"
+        );
+
+        let result = add_repos("repos: [] # none yet\n", &[repo("https://x/a.git", None)]).unwrap();
+        assert_eq!(result, "repos: # none yet\n  - url: \"https://x/a.git\"\n");
+    }
+
+    #[test]
+    fn test_add_repos_follows_existing_sequence_indentation() {
+        let content = "repos:\n- url: \"https://x/a.git\"\n";
+
+        let result = add_repos(content, &[repo("https://x/b.git", None)]).unwrap();
+
+        assert_eq!(
+            result,
+            "repos:\n- url: \"https://x/a.git\"\n- url: \"https://x/b.git\"\n"
+        );
+    }
+
+    #[test]
+    fn test_flow_lists_are_not_edited() {
+        let content = "repos: [{url: \"https://x/a.git\"}]\n";
+
+        assert!(matches!(
+            add_repos(content, &[repo("https://x/b.git", None)]),
+            Err(ConfigEditError::Unsupported(..))
+        ));
+    }
+
+    #[test]
+    fn test_add_groups_creates_nested_keys() {
+        let content = "groups:\n  github: []\n";
+        let group = NewGroup {
+            name: "platform/services".to_string(),
+            local_dir: Some("services".to_string()),
+            recursive: true,
+        };
+
+        let result = add_groups(content, "gitlab", &[group]).unwrap();
+
+        assert_eq!(
+            result,
+            "groups:\n  github: []\n  gitlab:\n    - name: \"platform/services\"\n      local_dir: \"services\"\n      recursive: true\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_repo_takes_its_comments_along() {
+        let content = "repos:\n\
+                       \x20 - url: \"https://x/a.git\"\n\
+                       \x20 # legacy, going away\n\
+                       \x20 - url: \"https://x/b.git\"\n\
+                       \x20   local_dir: \"old\"  # keep for now\n\
+                       \x20 - url: \"https://x/c.git\"\n\
+                       \n\
+                       # trailing comment\n";
+
+        let result = remove_repo(content, 1).unwrap();
+
+        assert_eq!(
+            result,
+            "repos:\n\
+             \x20 - url: \"https://x/a.git\"\n\
+             \x20 - url: \"https://x/c.git\"\n\
+             \n\
+             # trailing comment\n"
+        );
+    }
+
+    #[test]
+    fn test_removing_the_last_repo_leaves_an_empty_list() {
+        let content = "repos: # mine\n  - url: \"https://x/a.git\"\nurl_rewrites: []\n";
+
+        let result = remove_repo(content, 0).unwrap();
+
+        assert_eq!(result, "repos: [] # mine\nurl_rewrites: []\n");
+        assert!(remove_repo(&result, 0).is_err());
+    }
+
+    #[test]
+    fn test_line_endings_survive_a_round_trip() {
+        let content =
+            "# mine\r\nrepos:\r\n  - url: \"https://x/a.git\"\r\n\r\nurl_rewrites: []\r\n";
+
+        let added = add_repos(content, &[repo("https://x/b.git", None)]).unwrap();
+        assert_eq!(
+            added,
+            "# mine\r\nrepos:\r\n  - url: \"https://x/a.git\"\r\n  - url: \"https://x/b.git\"\r\n\r\nurl_rewrites: []\r\n"
+        );
+        assert_eq!(remove_repo(&added, 1).unwrap(), content);
+
+        // No final newline to begin with, none added
+        let content = "repos:\n  - url: \"https://x/a.git\"";
+        let added = add_repos(content, &[repo("https://x/b.git", None)]).unwrap();
+        assert_eq!(
+            added,
+            "repos:\n  - url: \"https://x/a.git\"\n  - url: \"https://x/b.git\""
+        );
+        assert_eq!(remove_repo(&added, 1).unwrap(), content);
+    }
+}
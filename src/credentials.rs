@@ -0,0 +1,389 @@
+//! Credential sources for `EnvString` values.
+//!
+//! Besides literals and `${ENV_VAR}`, a config value can name a source:
+//!
+//! - `file:~/.secrets/gitlab` - contents of a file (trailing newline removed)
+//! - `cmd: pass show gitlab` - stdout of a shell command
+//! - `git-credential` / `git-credential:https://host` - `git credential fill`
+//! - `netrc` / `netrc:host` - password for the host in `~/.netrc`
+//!
+//! The bare `git-credential` and `netrc` forms use the provider host.
+//! Errors name the source that failed but never include the secret itself.
+
+use crate::config::EnvResolutionError;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// A source a secret can be read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialSource {
+    File(String),
+    Command(String),
+    GitCredential(Option<String>),
+    Netrc(Option<String>),
+}
+
+impl CredentialSource {
+    /// Recognise a source reference, or `None` for plain values
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        if let Some(path) = value.strip_prefix("file:") {
+            return Some(CredentialSource::File(path.trim().to_string()));
+        }
+
+        if let Some(command) = value.strip_prefix("cmd:") {
+            return Some(CredentialSource::Command(command.trim().to_string()));
+        }
+
+        if value == "git-credential" {
+            return Some(CredentialSource::GitCredential(None));
+        }
+
+        if let Some(url) = value.strip_prefix("git-credential:") {
+            return Some(CredentialSource::GitCredential(non_empty(url)));
+        }
+
+        if value == "netrc" {
+            return Some(CredentialSource::Netrc(None));
+        }
+
+        if let Some(host) = value.strip_prefix("netrc:") {
+            return Some(CredentialSource::Netrc(non_empty(host)));
+        }
+
+        None
+    }
+
+    /// Short name used in error messages
+    pub fn name(&self) -> String {
+        match self {
+            CredentialSource::File(path) => format!("file:{}", path),
+            CredentialSource::Command(command) => {
+                // Only the program name: arguments may carry sensitive paths
+                let program = command.split_whitespace().next().unwrap_or("");
+                format!("cmd:{}", program)
+            }
+            CredentialSource::GitCredential(_) => "git-credential".to_string(),
+            CredentialSource::Netrc(_) => "netrc".to_string(),
+        }
+    }
+
+    /// Read the secret. `host` is the provider URL or host, used by the
+    /// `git-credential` and `netrc` sources when none is given explicitly.
+    pub fn resolve(&self, host: Option<&str>) -> Result<String, EnvResolutionError> {
+        let secret = match self {
+            CredentialSource::File(path) => read_file(path),
+            CredentialSource::Command(command) => run_command(command),
+            CredentialSource::GitCredential(url) => match url.as_deref().or(host) {
+                Some(url) => git_credential_fill(url),
+                None => Err("no provider host to ask for".to_string()),
+            },
+            CredentialSource::Netrc(machine) => match machine.as_deref().or(host) {
+                Some(target) => netrc_password(&host_of(target)),
+                None => Err("no provider host to look up".to_string()),
+            },
+        };
+
+        let secret = secret.map_err(|message| EnvResolutionError::SourceFailed {
+            source_name: self.name(),
+            message,
+        })?;
+
+        if secret.is_empty() {
+            return Err(EnvResolutionError::SourceFailed {
+                source_name: self.name(),
+                message: "returned an empty value".to_string(),
+            });
+        }
+
+        Ok(secret)
+    }
+}
+
+/// Expand a leading `~` to the user's home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    if path == "~" {
+        if let Some(home) = home_dir() {
+            return home;
+        }
+    }
+
+    if let Some(rest) = path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        if let Some(home) = home_dir() {
+            return home.join(rest);
+        }
+    }
+
+    PathBuf::from(path)
+}
+
+/// The current user's home directory, from `HOME` or `USERPROFILE`
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    let resolved = expand_home(path);
+    let content = std::fs::read_to_string(&resolved)
+        .map_err(|e| format!("cannot read {}: {}", resolved.display(), e.kind()))?;
+
+    Ok(content.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Run `command` through the platform shell
+pub fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+fn run_command(command: &str) -> Result<String, String> {
+    let output = shell_command(command)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("failed to start: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().next().unwrap_or("").trim();
+        return Err(format!("exited with {}: {}", output.status, reason));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Split a URL or bare host into the `protocol` and `host` fields git expects
+fn credential_request(url: &str) -> String {
+    let (protocol, rest) = match url.split_once("://") {
+        Some((protocol, rest)) => (protocol, rest),
+        None => ("https", url),
+    };
+    let host = rest.split('/').next().unwrap_or(rest);
+
+    format!("protocol={}\nhost={}\n\n", protocol, host)
+}
+
+fn git_credential_fill(url: &str) -> Result<String, String> {
+    let mut child = Command::new("git")
+        .arg("credential")
+        .arg("fill")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to start git: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(credential_request(url).as_bytes())
+            .map_err(|e| format!("failed to talk to git: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("no credential found for {}", host_of(url)));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .map(|password| password.to_string())
+        .ok_or_else(|| format!("no password returned for {}", host_of(url)))
+}
+
+/// Host part of a URL, or the value itself if it is already a host
+fn host_of(url: &str) -> String {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    host.split(':').next().unwrap_or(host).to_string()
+}
+
+fn netrc_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("NETRC") {
+        return Some(PathBuf::from(path));
+    }
+
+    let home = home_dir()?;
+    let netrc = home.join(".netrc");
+    if netrc.exists() || !cfg!(windows) {
+        Some(netrc)
+    } else {
+        Some(home.join("_netrc"))
+    }
+}
+
+fn netrc_password(host: &str) -> Result<String, String> {
+    let path = netrc_path().ok_or_else(|| "cannot locate home directory".to_string())?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e.kind()))?;
+
+    parse_netrc(&content, host)
+        .ok_or_else(|| format!("no entry for {} in {}", host, path.display()))
+}
+
+/// Find the password for `host` in netrc content, falling back to `default`
+fn parse_netrc(content: &str, host: &str) -> Option<String> {
+    let mut tokens = content.split_whitespace();
+    let mut current_matches = false;
+    let mut default_password = None;
+    let mut in_default = false;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => {
+                current_matches = tokens.next() == Some(host);
+                in_default = false;
+            }
+            "default" => {
+                current_matches = false;
+                in_default = true;
+            }
+            "password" => {
+                let password = tokens.next()?;
+                if current_matches {
+                    return Some(password.to_string());
+                }
+                if in_default && default_password.is_none() {
+                    default_password = Some(password.to_string());
+                }
+            }
+            "login" | "account" => {
+                tokens.next();
+            }
+            "macdef" => {
+                // Macro bodies run until a blank line, which whitespace
+                // splitting can't see; macros never define passwords, so
+                // simply stop matching the current machine.
+                current_matches = false;
+                in_default = false;
+            }
+            _ => {}
+        }
+    }
+
+    default_password
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            CredentialSource::parse("file:~/.secrets/gitlab"),
+            Some(CredentialSource::File("~/.secrets/gitlab".to_string()))
+        );
+        assert_eq!(
+            CredentialSource::parse("cmd: pass show gitlab"),
+            Some(CredentialSource::Command("pass show gitlab".to_string()))
+        );
+        assert_eq!(
+            CredentialSource::parse("git-credential"),
+            Some(CredentialSource::GitCredential(None))
+        );
+        assert_eq!(
+            CredentialSource::parse("netrc:gitlab.example.com"),
+            Some(CredentialSource::Netrc(Some(
+                "gitlab.example.com".to_string()
+            )))
+        );
+        assert_eq!(CredentialSource::parse("glpat-abc123"), None);
+    }
+
+    #[test]
+    fn test_file_source_trims_trailing_newline() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("token");
+        std::fs::write(&path, "file-secret\n").unwrap();
+
+        let source = CredentialSource::File(path.display().to_string());
+        assert_eq!(source.resolve(None).unwrap(), "file-secret");
+    }
+
+    #[test]
+    fn test_missing_file_error_names_source() {
+        let source = CredentialSource::File("/definitely/missing/token".to_string());
+        let error = source.resolve(None).unwrap_err().to_string();
+
+        assert!(error.contains("file:/definitely/missing/token"));
+    }
+
+    #[test]
+    fn test_command_source_reads_stdout() {
+        let source = CredentialSource::Command("echo cmd-secret".to_string());
+        assert_eq!(source.resolve(None).unwrap(), "cmd-secret");
+    }
+
+    #[test]
+    fn test_failing_command_error_does_not_leak_output() {
+        let source = CredentialSource::Command("echo leaked-secret && exit 3".to_string());
+        let error = source.resolve(None).unwrap_err().to_string();
+
+        assert!(error.contains("cmd:echo"));
+        assert!(!error.contains("leaked-secret"));
+    }
+
+    #[test]
+    fn test_parse_netrc_machine_and_default() {
+        let content = "machine other.example.com login me password other\n\
+                       machine gitlab.example.com\n  login me\n  password netrc-secret\n\
+                       default login anon password fallback\n";
+
+        assert_eq!(
+            parse_netrc(content, "gitlab.example.com").as_deref(),
+            Some("netrc-secret")
+        );
+        assert_eq!(
+            parse_netrc(content, "unknown.example.com").as_deref(),
+            Some("fallback")
+        );
+    }
+
+    #[test]
+    fn test_host_of_url() {
+        assert_eq!(
+            host_of("https://gitlab.example.com/api"),
+            "gitlab.example.com"
+        );
+        assert_eq!(
+            host_of("https://user@gitlab.example.com:8443"),
+            "gitlab.example.com"
+        );
+        assert_eq!(host_of("gitlab.example.com"), "gitlab.example.com");
+    }
+
+    #[test]
+    fn test_credential_request_keeps_port() {
+        assert_eq!(
+            credential_request("https://gitlab.example.com:8443/group"),
+            "protocol=https\nhost=gitlab.example.com:8443\n\n"
+        );
+    }
+}
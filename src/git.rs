@@ -0,0 +1,494 @@
+//! Read-only queries against local repositories.
+//!
+//! These shell out to `git` and treat any failure as "no answer", so callers
+//! reporting on a workspace never fail because one repo is unusual.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Stdout of `git -C repo <args>`, or `None` if git failed
+pub fn output(repo: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Run `git -C repo <args>`, returning stdout, or git's error message on failure
+pub fn run(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty())
+            .unwrap_or("git failed");
+        Err(message
+            .trim_start_matches("fatal: ")
+            .trim_start_matches("error: ")
+            .to_string())
+    }
+}
+
+/// Value of a setting in the repo's git config, if set
+pub fn config_value(repo: &Path, key: &str) -> Option<String> {
+    output(repo, &["config", "--get", key])
+}
+
+/// A `git_config` setting whose value in the repo differs from the configured one
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDrift {
+    pub key: String,
+    pub expected: String,
+    /// `None` if the key isn't set in the repo's own config
+    pub actual: Option<String>,
+}
+
+/// Compare the repo's local config (`.git/config`) with the expected values
+pub fn config_drift(repo: &Path, expected: &[(String, String)]) -> Vec<ConfigDrift> {
+    expected
+        .iter()
+        .filter_map(|(key, value)| {
+            let actual = output(repo, &["config", "--local", "--get", key]);
+            if actual.as_deref() == Some(value.as_str()) {
+                None
+            } else {
+                Some(ConfigDrift {
+                    key: key.clone(),
+                    expected: value.clone(),
+                    actual,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Whether `refname` (e.g. `refs/heads/main`) exists
+pub fn has_ref(repo: &Path, refname: &str) -> bool {
+    output(repo, &["rev-parse", "--verify", "--quiet", refname]).is_some()
+}
+
+/// Branch `origin/HEAD` points to, as of the last fetch
+pub fn origin_head(repo: &Path) -> Option<String> {
+    output(
+        repo,
+        &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+    )
+    .map(|head| head.trim_start_matches("origin/").to_string())
+}
+
+/// Checked out branch, or `None` on a detached HEAD
+pub fn current_branch(repo: &Path) -> Option<String> {
+    output(repo, &["symbolic-ref", "--short", "-q", "HEAD"])
+}
+
+/// Whether the clone has truncated history
+pub fn is_shallow(repo: &Path) -> bool {
+    repo.join(".git").join("shallow").exists()
+}
+
+/// Paths of submodules that are registered but not checked out
+pub fn missing_submodules(repo: &Path, recursive: bool) -> Vec<String> {
+    if !repo.join(".gitmodules").exists() {
+        return Vec::new();
+    }
+
+    let mut args = vec!["submodule", "status"];
+    if recursive {
+        args.push("--recursive");
+    }
+
+    output(repo, &args)
+        .map(|status| parse_missing_submodules(&status))
+        .unwrap_or_default()
+}
+
+/// Uninitialized submodules are the `git submodule status` lines starting with `-`
+fn parse_missing_submodules(status: &str) -> Vec<String> {
+    status
+        .lines()
+        .filter_map(|line| line.strip_prefix('-'))
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|path| path.to_string())
+        .collect()
+}
+
+/// Whether the repo tracks files with Git LFS
+pub fn uses_lfs(repo: &Path) -> bool {
+    std::fs::read_to_string(repo.join(".gitattributes"))
+        .map(|attributes| attributes.contains("filter=lfs"))
+        .unwrap_or(false)
+}
+
+/// Number of LFS files that are still pointers, or `None` if the repo
+/// doesn't use LFS or git-lfs isn't installed
+pub fn missing_lfs_objects(repo: &Path) -> Option<usize> {
+    if !uses_lfs(repo) {
+        return None;
+    }
+
+    output(repo, &["lfs", "ls-files"]).map(|files| count_missing_lfs_objects(&files))
+}
+
+/// `git lfs ls-files` marks downloaded files with `*` and pointers with `-`
+fn count_missing_lfs_objects(files: &str) -> usize {
+    files
+        .lines()
+        .filter(|line| line.split_whitespace().nth(1) == Some("-"))
+        .count()
+}
+
+/// Local state of a working tree, as far as updating it is concerned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoState {
+    Clean,
+    /// Uncommitted changes to tracked files
+    Dirty,
+    DetachedHead,
+    /// A rebase, merge, cherry-pick or revert was started and not finished
+    InProgress(&'static str),
+    /// The current branch and its upstream both have commits the other lacks
+    Diverged {
+        ahead: usize,
+        behind: usize,
+    },
+    /// The current branch has no upstream to update from
+    NoUpstream,
+}
+
+impl RepoState {
+    pub fn is_clean(&self) -> bool {
+        *self == RepoState::Clean
+    }
+}
+
+impl std::fmt::Display for RepoState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoState::Clean => write!(f, "clean"),
+            RepoState::Dirty => write!(f, "uncommitted changes"),
+            RepoState::DetachedHead => write!(f, "detached HEAD"),
+            RepoState::InProgress(operation) => write!(f, "{} in progress", operation),
+            RepoState::Diverged { ahead, behind } => {
+                write!(
+                    f,
+                    "diverged from upstream ({} ahead, {} behind)",
+                    ahead, behind
+                )
+            }
+            RepoState::NoUpstream => write!(f, "no upstream branch"),
+        }
+    }
+}
+
+/// Files git leaves in the git dir while an operation waits for the user
+const IN_PROGRESS_MARKERS: &[(&str, &str)] = &[
+    ("rebase-merge", "rebase"),
+    ("rebase-apply", "rebase"),
+    ("MERGE_HEAD", "merge"),
+    ("CHERRY_PICK_HEAD", "cherry-pick"),
+    ("REVERT_HEAD", "revert"),
+];
+
+/// Classify a checkout. Ahead/behind counts use the remote-tracking branch
+/// as last fetched. Submodules are ignored: updating the superproject
+/// never touches their working trees.
+pub fn repo_state(repo: &Path) -> RepoState {
+    if let Some(git_dir) = output(repo, &["rev-parse", "--absolute-git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        for (marker, operation) in IN_PROGRESS_MARKERS {
+            if git_dir.join(marker).exists() {
+                return RepoState::InProgress(operation);
+            }
+        }
+    }
+
+    if output(repo, &["symbolic-ref", "-q", "HEAD"]).is_none() {
+        return RepoState::DetachedHead;
+    }
+
+    if output(repo, &["rev-parse", "--abbrev-ref", "@{upstream}"]).is_none() {
+        return RepoState::NoUpstream;
+    }
+
+    let status = output(
+        repo,
+        &[
+            "status",
+            "--porcelain",
+            "--untracked-files=no",
+            "--ignore-submodules=all",
+        ],
+    );
+    if status.is_none_or(|status| !status.is_empty()) {
+        return RepoState::Dirty;
+    }
+
+    match ahead_behind(repo) {
+        Some((ahead, behind)) if ahead > 0 && behind > 0 => RepoState::Diverged { ahead, behind },
+        _ => RepoState::Clean,
+    }
+}
+
+/// Branch the remote `HEAD` points to, from `git ls-remote --symref <remote> HEAD`
+pub fn parse_symref_head(ls_remote: &str) -> Option<String> {
+    ls_remote
+        .lines()
+        .filter_map(|line| line.strip_prefix("ref: "))
+        .find_map(|line| line.strip_suffix("\tHEAD"))
+        .and_then(|target| target.strip_prefix("refs/heads/"))
+        .map(|branch| branch.to_string())
+}
+
+/// A local branch with commits that no remote-tracking branch contains
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnpushedBranch {
+    pub branch: String,
+    pub commits: usize,
+}
+
+/// Local branches with commits not on any remote, as of the last fetch
+pub fn unpushed_branches(repo: &Path) -> Vec<UnpushedBranch> {
+    let branches = output(
+        repo,
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+    )
+    .unwrap_or_default();
+
+    branches
+        .lines()
+        .filter_map(|branch| {
+            let commits = unpushed_commits(repo, branch)?;
+            (commits > 0).then(|| UnpushedBranch {
+                branch: branch.to_string(),
+                commits,
+            })
+        })
+        .collect()
+}
+
+/// Number of commits on `branch` that no remote-tracking branch contains
+pub fn unpushed_commits(repo: &Path, branch: &str) -> Option<usize> {
+    let refname = format!("refs/heads/{}", branch);
+    output(
+        repo,
+        &["rev-list", "--count", &refname, "--not", "--remotes"],
+    )?
+    .parse()
+    .ok()
+}
+
+/// A local branch and what cleanup needs to know about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalBranch {
+    pub name: String,
+    /// Upstream is configured but its remote branch no longer exists
+    pub upstream_gone: bool,
+    /// Committer date of the tip, in seconds since the epoch
+    pub commit_time: u64,
+    /// Checked out in this or another worktree
+    pub checked_out: bool,
+}
+
+const LOCAL_BRANCH_FORMAT: &str =
+    "--format=%(refname:short)%00%(upstream:track)%00%(committerdate:unix)%00%(worktreepath)";
+
+pub fn local_branches(repo: &Path) -> Vec<LocalBranch> {
+    output(repo, &["for-each-ref", LOCAL_BRANCH_FORMAT, "refs/heads"])
+        .map(|list| parse_local_branches(&list))
+        .unwrap_or_default()
+}
+
+fn parse_local_branches(list: &str) -> Vec<LocalBranch> {
+    list.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\0').collect();
+            Some(LocalBranch {
+                name: fields.first()?.to_string(),
+                upstream_gone: *fields.get(1)? == "[gone]",
+                commit_time: fields.get(2)?.parse().ok()?,
+                checked_out: !fields.get(3).unwrap_or(&"").is_empty(),
+            })
+        })
+        .collect()
+}
+
+/// Local branches whose tips are contained in `into`
+pub fn merged_branches(repo: &Path, into: &str) -> Vec<String> {
+    output(
+        repo,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)",
+            "--merged",
+            into,
+            "refs/heads",
+        ],
+    )
+    .map(|list| list.lines().map(|branch| branch.to_string()).collect())
+    .unwrap_or_default()
+}
+
+/// Stash entries, e.g. `stash@{0}: WIP on main: 3f1c2d0 message`
+pub fn stashes(repo: &Path) -> Vec<String> {
+    output(repo, &["stash", "list"])
+        .map(|list| list.lines().map(|line| line.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Untracked files that `.gitignore` doesn't exclude
+pub fn untracked_files(repo: &Path) -> Vec<String> {
+    output(repo, &["ls-files", "-z", "--others", "--exclude-standard"])
+        .map(|files| parse_nul_separated(&files))
+        .unwrap_or_default()
+}
+
+/// Tracked files with uncommitted changes, staged or not
+pub fn uncommitted_files(repo: &Path) -> Vec<String> {
+    output(
+        repo,
+        &[
+            "status",
+            "-z",
+            "--porcelain",
+            "--untracked-files=no",
+            "--ignore-submodules=dirty",
+        ],
+    )
+    .map(|status| parse_porcelain_paths(&status))
+    .unwrap_or_default()
+}
+
+fn parse_nul_separated(list: &str) -> Vec<String> {
+    list.split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| entry.to_string())
+        .collect()
+}
+
+/// Paths from `git status -z --porcelain`: `XY path`, where renames are
+/// followed by an extra entry holding the original path
+fn parse_porcelain_paths(status: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut entries = status.split('\0').filter(|entry| !entry.is_empty());
+
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        let (code, path) = entry.split_at(3);
+        paths.push(path.to_string());
+        if code.starts_with('R') || code.starts_with('C') {
+            entries.next();
+        }
+    }
+
+    paths
+}
+
+/// Commits on the current branch missing upstream, and the other way round
+pub fn ahead_behind(repo: &Path) -> Option<(usize, usize)> {
+    output(
+        repo,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )
+    .and_then(|counts| parse_ahead_behind(&counts))
+}
+
+fn parse_ahead_behind(counts: &str) -> Option<(usize, usize)> {
+    let mut parts = counts.split_whitespace();
+    let ahead = parts.next()?.parse().ok()?;
+    let behind = parts.next()?.parse().ok()?;
+    Some((ahead, behind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_missing_submodules() {
+        let status = " 3f1c2d0e libs/core (v1.2.0)\n\
+                      -9a8b7c6d libs/extra\n\
+                      +1234abcd vendor/tool (heads/main)\n\
+                      -feedbeef vendor/other";
+
+        assert_eq!(
+            parse_missing_submodules(status),
+            vec!["libs/extra", "vendor/other"]
+        );
+    }
+
+    #[test]
+    fn test_count_missing_lfs_objects() {
+        let files = "4d7a214614 * assets/logo.png\n\
+                     a1b2c3d4e5 - assets/video.mp4\n\
+                     0f9e8d7c6b - data/model.bin";
+
+        assert_eq!(count_missing_lfs_objects(files), 2);
+    }
+
+    #[test]
+    fn test_parse_symref_head() {
+        let output = "ref: refs/heads/trunk\tHEAD\n3f1c2d0e9a8b7c6d\tHEAD";
+
+        assert_eq!(parse_symref_head(output).as_deref(), Some("trunk"));
+        assert_eq!(parse_symref_head("3f1c2d0e9a8b7c6d\tHEAD"), None);
+    }
+
+    #[test]
+    fn test_parse_porcelain_paths_skips_rename_sources() {
+        let status = " M src/main.rs\0R  new name.rs\0old name.rs\0A  docs/added.md\0";
+
+        assert_eq!(
+            parse_porcelain_paths(status),
+            vec!["src/main.rs", "new name.rs", "docs/added.md"]
+        );
+    }
+
+    #[test]
+    fn test_parse_local_branches() {
+        let list = "main\x00\x001700000000\x00/src/app\n\
+                    feature/old\x00[gone]\x001600000000\x00\n\
+                    topic\x00[ahead 2]\x001650000000\x00";
+
+        let branches = parse_local_branches(list);
+
+        assert_eq!(branches.len(), 3);
+        assert!(branches[0].checked_out && !branches[0].upstream_gone);
+        assert_eq!(branches[1].name, "feature/old");
+        assert!(branches[1].upstream_gone && !branches[1].checked_out);
+        assert_eq!(branches[2].commit_time, 1650000000);
+        assert!(!branches[2].upstream_gone);
+    }
+
+    #[test]
+    fn test_parse_ahead_behind() {
+        assert_eq!(parse_ahead_behind("2\t5"), Some((2, 5)));
+        assert_eq!(parse_ahead_behind("0\t0"), Some((0, 0)));
+        assert_eq!(parse_ahead_behind(""), None);
+    }
+}
@@ -3,4 +3,8 @@
 
 pub mod commands;
 pub mod config;
+pub mod config_edit;
+pub mod credentials;
+pub mod git;
 pub mod providers;
+pub mod redact;
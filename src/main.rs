@@ -1,6 +1,10 @@
 mod commands;
 mod config;
+mod config_edit;
+mod credentials;
+mod git;
 mod providers;
+mod redact;
 
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
@@ -23,6 +27,28 @@ enum Commands {
         dir: Option<PathBuf>,
     },
     
+    /// Add a standalone repo to ranger.yaml
+    Add {
+        /// Clone URL of the repo
+        url: String,
+
+        /// Directory to clone into, relative to the workspace
+        #[arg(long, value_name = "DIR")]
+        dir: Option<String>,
+    },
+
+    /// Remove a standalone repo from ranger.yaml (the clone is kept)
+    Remove {
+        /// Repo URL or name
+        repo: String,
+    },
+
+    /// Manage the groups in ranger.yaml
+    Group {
+        #[command(subcommand)]
+        command: GroupCommands,
+    },
+
     /// Synchronize workspace: clone missing repos and fetch updates
     Sync {
         /// Target to sync (group name or repo URL, syncs all if not specified)
@@ -31,6 +57,14 @@ enum Commands {
         /// Preview what would happen without making changes
         #[arg(short = 'n', long)]
         dry_run: bool,
+
+        /// Ignore cached provider responses and re-download group listings
+        #[arg(long)]
+        refresh: bool,
+
+        /// Convert shallow, partial and sparse clones into full clones
+        #[arg(long)]
+        unshallow: bool,
     },
     
     /// Show status of all configured repos
@@ -38,6 +72,136 @@ enum Commands {
     
     /// List all repos from config with their local paths
     Ls,
+
+    /// Create a branch from the default branch in every repo and check it out
+    Branch {
+        /// Branch name
+        name: String,
+
+        /// Repos to work on (group name or repo URL, all if not specified)
+        target: Option<String>,
+
+        /// List which repos have the branch, locally or on origin, instead of creating it
+        #[arg(short, long)]
+        list: bool,
+    },
+
+    /// Check out an existing branch in every repo that has it
+    Checkout {
+        /// Branch name
+        name: String,
+
+        /// Repos to work on (group name or repo URL, all if not specified)
+        target: Option<String>,
+
+        /// Branch to check out in repos that don't have the requested one
+        #[arg(long, value_name = "BRANCH")]
+        fallback: Option<String>,
+    },
+
+    /// Find merged and stale local branches in every repo, and optionally delete them
+    Branches {
+        /// Repos to check (group name or repo URL, all if not specified)
+        target: Option<String>,
+
+        /// Branches merged into the default branch or whose upstream is gone (the default)
+        #[arg(long)]
+        merged: bool,
+
+        /// Branches without commits for at least this many days
+        #[arg(long, value_name = "DAYS")]
+        stale: Option<u64>,
+
+        /// Ask before deleting each branch found
+        #[arg(long)]
+        delete: bool,
+
+        /// Delete every branch found without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Find work that exists only locally: unpushed commits, stashes, untracked files
+    Audit {
+        /// Repos to check (group name or repo URL, all if not specified)
+        target: Option<String>,
+    },
+
+    /// Write the repo list for another tool: mrconfig, gita, repo-manifest or vscode-workspace
+    Export {
+        /// Repos to export (group name or repo URL, all if not specified)
+        target: Option<String>,
+
+        /// Output format
+        #[arg(long, value_name = "FORMAT")]
+        to: commands::export::ExportFormat,
+
+        /// File to write instead of printing to stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Add the git repos found in a directory tree to ranger.yaml, creating it if needed
+    Import {
+        /// Directory to scan
+        dir: PathBuf,
+
+        /// Show what would be added without changing ranger.yaml
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Find git repos inside the workspace that ranger.yaml doesn't manage
+    Orphans {
+        /// Add them to ranger.yaml as standalone repos
+        #[arg(long)]
+        add: bool,
+    },
+
+    /// Search all repos with git grep
+    Grep {
+        /// Pattern to search for (git grep syntax)
+        pattern: String,
+
+        /// Repos to search (group name or repo URL, all if not specified)
+        target: Option<String>,
+
+        /// Search this branch, tag or commit instead of the working tree
+        #[arg(long, value_name = "REF")]
+        rev: Option<String>,
+
+        /// Only list the files that match
+        #[arg(short = 'l', long)]
+        files_with_matches: bool,
+
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: commands::grep::OutputFormat,
+
+        /// Number of repos searched at once (defaults to the number of CPUs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GroupCommands {
+    /// Add a GitLab group or GitHub organization to ranger.yaml
+    Add {
+        /// Provider: gitlab or github
+        provider: String,
+
+        /// Group path (e.g. platform/services) or organization name
+        path: String,
+
+        /// Include the projects of all subgroups
+        #[arg(long)]
+        recursive: bool,
+
+        /// Directory to clone into, relative to the workspace
+        #[arg(long, value_name = "DIR")]
+        dir: Option<String>,
+    },
 }
 
 fn main() {
@@ -56,30 +220,94 @@ fn main() {
                     Ok(())
                 }
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
                     Err(1)
                 }
             }
         }
-        Commands::Sync { target, dry_run } => {
+        Commands::Add { url, dir } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::edit::AddOptions {
+                config_path,
+                url,
+                dir,
+            };
+
+            match commands::edit::add_command(&options) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Remove { repo } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::edit::RemoveOptions { config_path, repo };
+
+            match commands::edit::remove_command(&options) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Group {
+            command:
+                GroupCommands::Add {
+                    provider,
+                    path,
+                    recursive,
+                    dir,
+                },
+        } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::edit::GroupAddOptions {
+                config_path,
+                provider,
+                path,
+                recursive,
+                dir,
+            };
+
+            match commands::edit::group_add_command(&options) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Sync {
+            target,
+            dry_run,
+            refresh,
+            unshallow,
+        } => {
             let config_path = PathBuf::from(".").join("ranger.yaml");
             
             let options = commands::sync::SyncOptions {
                 config_path,
                 target,
                 dry_run,
+                refresh,
+                unshallow,
             };
             
             match commands::sync::sync_command(&options) {
                 Ok(report) => {
-                    if report.errors.is_empty() {
+                    if report.errors.is_empty() && report.hook_failures.is_empty() {
                         Ok(())
                     } else {
                         Err(1)
                     }
                 }
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
                     Err(1)
                 }
             }
@@ -94,7 +322,7 @@ fn main() {
             match commands::status::status_command(&options) {
                 Ok(_) => Ok(()),
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
                     Err(1)
                 }
             }
@@ -109,11 +337,189 @@ fn main() {
             match commands::ls::ls_command(&options) {
                 Ok(_) => Ok(()),
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
                     Err(1)
                 }
             }
         }
+        Commands::Branch { name, target, list } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::branch::BranchOptions {
+                config_path,
+                target,
+                branch: name,
+            };
+
+            let result = if list {
+                commands::branch::branch_list_command(&options).map(|_| 0)
+            } else {
+                commands::branch::branch_create_command(&options).map(|report| report.failures())
+            };
+
+            match result {
+                Ok(0) => Ok(()),
+                Ok(_) => Err(1),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Checkout {
+            name,
+            target,
+            fallback,
+        } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::branch::CheckoutOptions {
+                config_path,
+                target,
+                branch: name,
+                fallback,
+            };
+
+            match commands::branch::checkout_command(&options) {
+                Ok(report) if report.failures() == 0 => Ok(()),
+                Ok(_) => Err(1),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Branches {
+            target,
+            merged,
+            stale,
+            delete,
+            yes,
+        } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let delete = if yes {
+                commands::branches::DeleteMode::Yes
+            } else if delete {
+                commands::branches::DeleteMode::Ask
+            } else {
+                commands::branches::DeleteMode::Report
+            };
+
+            let options = commands::branches::BranchesOptions {
+                config_path,
+                target,
+                merged: merged || stale.is_none(),
+                stale_days: stale,
+                delete,
+            };
+
+            match commands::branches::branches_command(&options) {
+                Ok(report) if report.errors() > 0 => Err(1),
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Audit { target } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::audit::AuditOptions {
+                config_path,
+                target,
+            };
+
+            match commands::audit::audit_command(&options) {
+                Ok(report) if report.has_findings() => Err(1),
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Export { target, to, output } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::export::ExportOptions {
+                config_path,
+                target,
+                format: to,
+                output,
+            };
+
+            match commands::export::export_command(&options) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Import { dir, dry_run } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::import::ImportOptions {
+                config_path,
+                dir,
+                dry_run,
+            };
+
+            match commands::import::import_command(&options) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Orphans { add } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::orphans::OrphansOptions { config_path, add };
+
+            match commands::orphans::orphans_command(&options) {
+                Ok(report) if report.has_findings() => Err(1),
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(1)
+                }
+            }
+        }
+        Commands::Grep {
+            pattern,
+            target,
+            rev,
+            files_with_matches,
+            format,
+            jobs,
+        } => {
+            let config_path = PathBuf::from(".").join("ranger.yaml");
+
+            let options = commands::grep::GrepOptions {
+                config_path,
+                target,
+                pattern,
+                rev,
+                files_with_matches,
+                format,
+                jobs,
+            };
+
+            // Like grep: 1 when nothing matched, 2 on errors
+            match commands::grep::grep_command(&options) {
+                Ok(report) if report.errors() > 0 => Err(2),
+                Ok(report) if report.total_matches() == 0 => Err(1),
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    eprintln!("Error: {}", redact::redact(&e.to_string()));
+                    Err(2)
+                }
+            }
+        }
     };
 
     if let Err(code) = result {
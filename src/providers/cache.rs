@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directory (relative to the workspace root) holding cached API responses
+pub const CACHE_DIR: &str = ".git-ranger/cache/http";
+
+/// Response headers worth replaying from the cache. Pagination headers are
+/// needed so a cached page still points at the next one.
+const REPLAYED_HEADERS: &[&str] = &[
+    "content-type",
+    "link",
+    "x-next-page",
+    "x-page",
+    "x-total",
+    "x-total-pages",
+];
+
+/// A cached API response together with its validators
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CachedResponse {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl CachedResponse {
+    /// Build a cache entry from a successful response.
+    /// Returns `None` when the server sent no validators, since such an
+    /// entry could never be revalidated.
+    pub fn from_response(
+        url: &str,
+        headers: &reqwest::header::HeaderMap,
+        body: &[u8],
+    ) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+
+        let etag = header("etag");
+        let last_modified = header("last-modified");
+
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+
+        let replayed = REPLAYED_HEADERS
+            .iter()
+            .filter_map(|name| header(name).map(|value| (name.to_string(), value)))
+            .collect();
+
+        Some(Self {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            headers: replayed,
+            body: String::from_utf8_lossy(body).into_owned(),
+        })
+    }
+}
+
+/// On-disk cache of provider API responses, keyed by request URL.
+///
+/// Entries are only used for conditional requests (`If-None-Match` /
+/// `If-Modified-Since`); the server always decides whether they are fresh.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    refresh: bool,
+}
+
+impl ResponseCache {
+    /// Create a cache stored under `workspace_dir`.
+    /// With `refresh` set, existing entries are ignored but still rewritten.
+    pub fn new(workspace_dir: &Path, refresh: bool) -> Self {
+        Self {
+            dir: workspace_dir.join(CACHE_DIR),
+            refresh,
+        }
+    }
+
+    /// Look up the entry for `url`, unless refreshing
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        if self.refresh {
+            return None;
+        }
+
+        let content = std::fs::read_to_string(self.entry_path(url)).ok()?;
+        let entry: CachedResponse = serde_json::from_str(&content).ok()?;
+
+        // Guard against hash collisions
+        if entry.url == url {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    /// Store an entry. Failures are ignored: the cache is only an optimisation.
+    pub fn put(&self, entry: &CachedResponse) {
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+
+        if let Ok(content) = serde_json::to_string(entry) {
+            let _ = std::fs::write(self.entry_path(&entry.url), content);
+        }
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(url.as_bytes())))
+    }
+}
+
+/// FNV-1a, used for cache file names because it is stable across builds
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+
+    fn entry(url: &str) -> CachedResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert("etag", "W/\"abc\"".parse().unwrap());
+        headers.insert("x-next-page", "2".parse().unwrap());
+        CachedResponse::from_response(url, &headers, b"[]").unwrap()
+    }
+
+    #[test]
+    fn test_response_without_validators_is_not_cached() {
+        assert!(CachedResponse::from_response("http://x", &HeaderMap::new(), b"[]").is_none());
+    }
+
+    #[test]
+    fn test_cache_roundtrip_keeps_pagination_headers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(temp_dir.path(), false);
+
+        cache.put(&entry("http://gitlab/api/v4/groups/g/projects"));
+        let cached = cache.get("http://gitlab/api/v4/groups/g/projects").unwrap();
+
+        assert_eq!(cached.etag.as_deref(), Some("W/\"abc\""));
+        assert_eq!(
+            cached.headers.get("x-next-page").map(String::as_str),
+            Some("2")
+        );
+        assert!(cache.get("http://gitlab/other").is_none());
+    }
+
+    #[test]
+    fn test_refresh_ignores_existing_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        ResponseCache::new(temp_dir.path(), false).put(&entry("http://gitlab/x"));
+
+        assert!(ResponseCache::new(temp_dir.path(), true)
+            .get("http://gitlab/x")
+            .is_none());
+        assert!(ResponseCache::new(temp_dir.path(), false)
+            .get("http://gitlab/x")
+            .is_some());
+    }
+}
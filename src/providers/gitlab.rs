@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use crate::config::{ConnectionSettings, RetryConfig};
+use super::cache::ResponseCache;
+use super::http::{HttpClient, HttpError, HttpResponse};
+use crate::redact::redact;
 
 #[derive(Error, Debug)]
 pub enum GitLabError {
@@ -14,6 +18,19 @@ pub enum GitLabError {
     
     #[error("Group not found: {0}")]
     GroupNotFound(String),
+
+    #[error("Rate limited by GitLab, retries exhausted: {0}")]
+    RateLimited(String),
+
+    #[error("Pagination limit of {pages} pages reached while listing {resource} ({fetched} items fetched); results would be incomplete")]
+    PaginationLimitExceeded {
+        resource: String,
+        pages: usize,
+        fetched: usize,
+    },
+
+    #[error("Keyset pagination is not supported for this request")]
+    KeysetNotSupported,
 }
 
 /// GitLab project information from API
@@ -25,30 +42,92 @@ pub struct GitLabProject {
     pub path_with_namespace: String,
     pub ssh_url_to_repo: String,
     pub http_url_to_repo: String,
+    #[serde(default)]
+    pub wiki_enabled: bool,
+    /// `None` for empty projects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// Parent project, when this project is a fork
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from_project: Option<GitLabForkParent>,
+}
+
+/// The parts of a fork's parent project that we use
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct GitLabForkParent {
+    pub path_with_namespace: String,
+    pub ssh_url_to_repo: String,
+    pub http_url_to_repo: String,
+}
+
+/// Number of items requested per page
+const PER_PAGE: usize = 100;
+
+/// Default upper bound on the number of pages fetched for a single listing
+const DEFAULT_MAX_PAGES: usize = 1000;
+
+impl From<HttpError> for GitLabError {
+    fn from(error: HttpError) -> Self {
+        GitLabError::RequestFailed(redact(&error.to_string()))
+    }
 }
 
 /// GitLab API client
 pub struct GitLabClient {
     base_url: String,
     token: String,
-    client: reqwest::blocking::Client,
+    client: HttpClient,
+    max_pages: usize,
 }
 
 impl GitLabClient {
-    /// Create a new GitLab client
+    /// Create a new GitLab client with the default retry policy
+    #[allow(dead_code)]
     pub fn new(base_url: String, token: String) -> Result<Self, GitLabError> {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .map_err(|e| GitLabError::RequestFailed(e.to_string()))?;
+        Self::with_retry(base_url, token, RetryConfig::default())
+    }
+
+    /// Create a new GitLab client with a custom retry policy
+    pub fn with_retry(
+        base_url: String,
+        token: String,
+        retry: RetryConfig,
+    ) -> Result<Self, GitLabError> {
+        Self::with_settings(base_url, token, retry, &ConnectionSettings::default())
+    }
+
+    /// Create a new GitLab client with a custom retry policy and connection
+    /// settings (CA bundle, client certificate, proxy, timeout)
+    pub fn with_settings(
+        base_url: String,
+        token: String,
+        retry: RetryConfig,
+        connection: &ConnectionSettings,
+    ) -> Result<Self, GitLabError> {
+        let client = HttpClient::new(retry, connection)?;
         
         Ok(Self {
             base_url,
             token,
             client,
+            max_pages: DEFAULT_MAX_PAGES,
         })
     }
     
+    /// Cache API responses on disk and revalidate them with ETags
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.client = self.client.with_cache(cache);
+        self
+    }
+
+    /// Set the maximum number of pages fetched for a single listing.
+    /// Exceeding the limit is reported as `GitLabError::PaginationLimitExceeded`.
+    #[allow(dead_code)]
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
     /// Get all projects in a group
     /// If recursive is true, includes projects from subgroups
     pub fn get_group_projects(
@@ -56,35 +135,30 @@ impl GitLabClient {
         group_path: &str,
         recursive: bool,
     ) -> Result<Vec<GitLabProject>, GitLabError> {
-        // URL encode the group path
-        let encoded_path = urlencoding::encode(group_path);
-        
-        // Build URL - if recursive, use different endpoint
-        let endpoint = if recursive {
-            format!(
-                "{}/api/v4/groups/{}/projects?include_subgroups=true&per_page=100",
-                self.base_url, encoded_path
-            )
-        } else {
-            format!(
-                "{}/api/v4/groups/{}/projects?per_page=100",
-                self.base_url, encoded_path
-            )
-        };
-        
+        // Keyset pagination is requested first; instances or endpoints that
+        // don't support it either ignore the parameter or answer 405, in
+        // which case we fall back to offset pagination.
+        match self.get_group_projects_paginated(group_path, recursive, true) {
+            Err(GitLabError::KeysetNotSupported) => {
+                self.get_group_projects_paginated(group_path, recursive, false)
+            }
+            other => other,
+        }
+    }
+
+    fn get_group_projects_paginated(
+        &self,
+        group_path: &str,
+        recursive: bool,
+        keyset: bool,
+    ) -> Result<Vec<GitLabProject>, GitLabError> {
+        let mut url = self.group_projects_url(group_path, recursive, keyset);
         let mut all_projects = Vec::new();
         let mut page = 1;
         
-        // GitLab uses pagination
         loop {
-            let url = format!("{}&page={}", endpoint, page);
-            
-            let response = self.client
-                .get(&url)
-                .header("PRIVATE-TOKEN", &self.token)
-                .send()
-                .map_err(|e| GitLabError::RequestFailed(e.to_string()))?;
-            
+            let response = self.client.get(&url, &[("PRIVATE-TOKEN", &self.token)])?;
+
             // Check for auth errors
             if response.status() == 401 || response.status() == 403 {
                 return Err(GitLabError::AuthenticationFailed(
@@ -97,35 +171,282 @@ impl GitLabClient {
                 return Err(GitLabError::GroupNotFound(group_path.to_string()));
             }
             
+            if response.status() == 429 {
+                return Err(GitLabError::RateLimited(format!(
+                    "listing projects of group '{}'",
+                    group_path
+                )));
+            }
+
+            if keyset && response.status() == 405 {
+                return Err(GitLabError::KeysetNotSupported);
+            }
+
             // Check for other errors
             if !response.status().is_success() {
-                return Err(GitLabError::RequestFailed(format!(
+                return Err(GitLabError::RequestFailed(redact(&format!(
                     "HTTP {}: {}",
                     response.status(),
-                    response.text().unwrap_or_default()
-                )));
+                    response.text()
+                ))));
             }
             
+            let next = next_page(response.headers(), &url, page);
+
             let projects: Vec<GitLabProject> = response
                 .json()
-                .map_err(|e| GitLabError::ParseError(e.to_string()))?;
-            
-            // If no more projects, we're done
-            if projects.is_empty() {
-                break;
-            }
+                .map_err(|e| GitLabError::ParseError(redact(&e.to_string())))?;
             
+            let page_len = projects.len();
             all_projects.extend(projects);
-            page += 1;
             
-            // Safety limit to avoid infinite loops
-            if page > 100 {
-                break;
+            let next_url = match next {
+                NextPage::Url(next_url) => next_url,
+                NextPage::Done => break,
+                // No pagination headers at all: a short page is the last one
+                NextPage::Unknown(next_url) => {
+                    if page_len < PER_PAGE {
+                        break;
+                    }
+                    next_url
+                }
+            };
+
+            if page >= self.max_pages {
+                return Err(GitLabError::PaginationLimitExceeded {
+                    resource: format!("projects of group '{}'", group_path),
+                    pages: self.max_pages,
+                    fetched: all_projects.len(),
+                });
             }
+
+            url = next_url;
+            page += 1;
         }
         
         Ok(all_projects)
     }
+
+    /// Look up a project by its full path; `None` if it doesn't exist
+    pub fn get_project(&self, project_path: &str) -> Result<Option<GitLabProject>, GitLabError> {
+        let url = format!(
+            "{}/api/v4/projects/{}",
+            self.base_url,
+            urlencoding::encode(project_path)
+        );
+        let response = self.client.get(&url, &[("PRIVATE-TOKEN", &self.token)])?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+        check_status(&response, || {
+            format!("looking up project '{}'", project_path)
+        })?;
+
+        response
+            .json()
+            .map(Some)
+            .map_err(|e| GitLabError::ParseError(redact(&e.to_string())))
+    }
+
+    /// Create an empty project `name` in the group or user namespace at
+    /// `namespace_path`. Missing subgroups on the way are created; a missing
+    /// top-level group is an error.
+    pub fn create_project(
+        &self,
+        namespace_path: &str,
+        name: &str,
+    ) -> Result<GitLabProject, GitLabError> {
+        let namespace_id = self.ensure_namespace(namespace_path)?;
+
+        let url = format!("{}/api/v4/projects", self.base_url);
+        let body = serde_json::json!({
+            "name": name,
+            "path": name,
+            "namespace_id": namespace_id,
+        });
+        let response = self
+            .client
+            .post_json(&url, &[("PRIVATE-TOKEN", &self.token)], &body)?;
+        check_status(&response, || {
+            format!("creating project '{}/{}'", namespace_path, name)
+        })?;
+
+        response
+            .json()
+            .map_err(|e| GitLabError::ParseError(redact(&e.to_string())))
+    }
+
+    /// ID of the namespace at `namespace_path`, creating it as a subgroup
+    /// of its parent (recursively) if it doesn't exist
+    fn ensure_namespace(&self, namespace_path: &str) -> Result<u64, GitLabError> {
+        let url = format!(
+            "{}/api/v4/namespaces/{}",
+            self.base_url,
+            urlencoding::encode(namespace_path)
+        );
+        let response = self.client.get(&url, &[("PRIVATE-TOKEN", &self.token)])?;
+
+        if response.status() != 404 {
+            check_status(&response, || {
+                format!("looking up namespace '{}'", namespace_path)
+            })?;
+            let namespace: GitLabNamespace = response
+                .json()
+                .map_err(|e| GitLabError::ParseError(redact(&e.to_string())))?;
+            return Ok(namespace.id);
+        }
+
+        let (parent_path, path) = match namespace_path.rsplit_once('/') {
+            Some(split) => split,
+            None => return Err(GitLabError::GroupNotFound(namespace_path.to_string())),
+        };
+        let parent_id = self.ensure_namespace(parent_path)?;
+
+        let url = format!("{}/api/v4/groups", self.base_url);
+        let body = serde_json::json!({
+            "name": path,
+            "path": path,
+            "parent_id": parent_id,
+        });
+        let response = self
+            .client
+            .post_json(&url, &[("PRIVATE-TOKEN", &self.token)], &body)?;
+        check_status(&response, || format!("creating group '{}'", namespace_path))?;
+
+        let group: GitLabNamespace = response
+            .json()
+            .map_err(|e| GitLabError::ParseError(redact(&e.to_string())))?;
+        Ok(group.id)
+    }
+
+    fn group_projects_url(&self, group_path: &str, recursive: bool, keyset: bool) -> String {
+        // URL encode the group path
+        let encoded_path = urlencoding::encode(group_path);
+
+        let mut url = format!(
+            "{}/api/v4/groups/{}/projects?per_page={}&order_by=id&sort=asc",
+            self.base_url, encoded_path, PER_PAGE
+        );
+
+        if recursive {
+            url.push_str("&include_subgroups=true");
+        }
+
+        if keyset {
+            url.push_str("&pagination=keyset");
+        }
+
+        url
+    }
+}
+
+/// GitLab group or user namespace, or a group just created
+#[derive(Debug, Deserialize)]
+struct GitLabNamespace {
+    id: u64,
+}
+
+/// Map error statuses of a single-resource request to `GitLabError`
+fn check_status(response: &HttpResponse, action: impl Fn() -> String) -> Result<(), GitLabError> {
+    let status = response.status();
+
+    if status == 401 || status == 403 {
+        return Err(GitLabError::AuthenticationFailed(format!(
+            "Not allowed: {}",
+            action()
+        )));
+    }
+
+    if status == 429 {
+        return Err(GitLabError::RateLimited(action()));
+    }
+
+    if !status.is_success() {
+        return Err(GitLabError::RequestFailed(redact(&format!(
+            "HTTP {} {}: {}",
+            status,
+            action(),
+            response.text()
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Where to go after the current page
+#[derive(Debug, PartialEq)]
+enum NextPage {
+    /// The server told us the next page
+    Url(String),
+    /// The server told us this was the last page
+    Done,
+    /// The server sent no pagination headers; this is our best guess
+    Unknown(String),
+}
+
+/// Work out the next page from GitLab's pagination headers.
+/// `Link: <...>; rel="next"` is used for both keyset and offset pagination,
+/// `X-Next-Page` is the offset-only fallback.
+fn next_page(headers: &reqwest::header::HeaderMap, current_url: &str, page: usize) -> NextPage {
+    if let Some(link) = headers.get("link").and_then(|v| v.to_str().ok()) {
+        if let Some(next_url) = parse_next_link(link) {
+            return NextPage::Url(next_url);
+        }
+    }
+
+    let page_url = |page: &str| {
+        let base = strip_query_param(current_url, "page");
+        let separator = if base.contains('?') { '&' } else { '?' };
+        format!("{}{}page={}", base, separator, page)
+    };
+
+    match headers.get("x-next-page").and_then(|v| v.to_str().ok()) {
+        Some(next) if !next.trim().is_empty() => NextPage::Url(page_url(next.trim())),
+        Some(_) => NextPage::Done,
+        None if headers.contains_key("link") => NextPage::Done,
+        None => NextPage::Unknown(page_url(&(page + 1).to_string())),
+    }
+}
+
+/// Extract the `rel="next"` target from an RFC 8288 `Link` header
+fn parse_next_link(link: &str) -> Option<String> {
+    link.split(',').find_map(|entry| {
+        let mut parts = entry.split(';');
+        let target = parts.next()?.trim();
+        let is_next = parts.any(|param| {
+            let param = param.trim();
+            param == "rel=\"next\"" || param == "rel=next"
+        });
+
+        if is_next {
+            target
+                .strip_prefix('<')
+                .and_then(|t| t.strip_suffix('>'))
+                .map(|t| t.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+fn strip_query_param(url: &str, name: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some(parts) => parts,
+        None => return url.to_string(),
+    };
+
+    let prefix = format!("{}=", name);
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.starts_with(&prefix))
+        .collect();
+
+    if kept.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}?{}", base, kept.join("&"))
+    }
 }
 
 #[cfg(test)]
@@ -166,4 +487,54 @@ mod tests {
         let encoded = urlencoding::encode(path);
         assert_eq!(encoded, "parent%2Fchild%2Fgrandchild");
     }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> reqwest::header::HeaderMap {
+        let mut map = reqwest::header::HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_next_link() {
+        let link = r#"<https://gitlab.example.com/api/v4/projects?id_after=42>; rel="next", <https://gitlab.example.com/api/v4/projects?page=1>; rel="first""#;
+        assert_eq!(
+            parse_next_link(link),
+            Some("https://gitlab.example.com/api/v4/projects?id_after=42".to_string())
+        );
+
+        let last_page = r#"<https://gitlab.example.com/api/v4/projects?page=1>; rel="first""#;
+        assert_eq!(parse_next_link(last_page), None);
+    }
+
+    #[test]
+    fn test_next_page_from_x_next_page_header() {
+        let url = "https://gitlab.example.com/api/v4/groups/g/projects?per_page=100&page=1";
+
+        assert_eq!(
+            next_page(&headers(&[("x-next-page", "2")]), url, 1),
+            NextPage::Url(
+                "https://gitlab.example.com/api/v4/groups/g/projects?per_page=100&page=2"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            next_page(&headers(&[("x-next-page", "")]), url, 1),
+            NextPage::Done
+        );
+    }
+
+    #[test]
+    fn test_next_page_without_headers_is_unknown() {
+        let url = "https://gitlab.example.com/api/v4/groups/g/projects?per_page=100";
+
+        assert_eq!(
+            next_page(&headers(&[]), url, 1),
+            NextPage::Unknown(
+                "https://gitlab.example.com/api/v4/groups/g/projects?per_page=100&page=2"
+                    .to_string()
+            )
+        );
+    }
 }
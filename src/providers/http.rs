@@ -0,0 +1,526 @@
+use super::cache::{CachedResponse, ResponseCache};
+use crate::config::{ConnectionSettings, RetryConfig, DEFAULT_TIMEOUT_SECS};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HttpError {
+    #[error("Failed to build HTTP client: {0}")]
+    ClientBuildFailed(String),
+
+    #[error("Failed to load {what} from {path}: {message}")]
+    CertificateError {
+        what: &'static str,
+        path: String,
+        message: String,
+    },
+
+    #[error("Request to {url} failed after {attempts} attempt(s): {message}")]
+    RequestFailed {
+        url: String,
+        attempts: u32,
+        message: String,
+    },
+
+    #[error("Server asked to wait {}s before the next request to {url}, longer than retry.max_backoff_ms ({limit_ms} ms); try again later", wait.as_secs())]
+    WaitTooLong {
+        url: String,
+        wait: Duration,
+        limit_ms: u64,
+    },
+}
+
+/// A fully read HTTP response, either from the network or replayed from cache
+#[derive(Debug)]
+pub struct HttpResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    from_cache: bool,
+}
+
+impl HttpResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Whether the body was served from the response cache after a 304
+    #[allow(dead_code)]
+    pub fn is_cached(&self) -> bool {
+        self.from_cache
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+
+    fn read(response: Response) -> Result<Self, reqwest::Error> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes()?.to_vec();
+
+        Ok(Self {
+            status,
+            headers,
+            body,
+            from_cache: false,
+        })
+    }
+
+    fn from_cached(entry: CachedResponse) -> Self {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &entry.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+
+        Self {
+            status: StatusCode::OK,
+            headers,
+            body: entry.body.into_bytes(),
+            from_cache: true,
+        }
+    }
+}
+
+/// Shared HTTP layer for provider API clients.
+///
+/// Idempotent requests are retried with exponential backoff and jitter on
+/// transport errors, 429 and 5xx responses. Server hints (`Retry-After`,
+/// `RateLimit-Remaining`/`RateLimit-Reset`) take precedence over the
+/// computed backoff and are waited out in full; a request that would have
+/// to wait longer than `max_backoff_ms` fails instead of going out early.
+///
+/// With a `ResponseCache` attached, GET requests are made conditional and a
+/// `304 Not Modified` is answered from the cache.
+pub struct HttpClient {
+    client: Client,
+    retry: RetryConfig,
+    cache: Option<ResponseCache>,
+    /// Earliest time the next request may be sent, set when the server
+    /// reports that the rate limit window is exhausted
+    not_before: Mutex<Option<Instant>>,
+}
+
+impl HttpClient {
+    /// Create a new HTTP client with the given retry policy and
+    /// connection settings (CA bundle, client certificate, proxy, timeout)
+    pub fn new(retry: RetryConfig, connection: &ConnectionSettings) -> Result<Self, HttpError> {
+        let timeout = connection
+            .timeout
+            .unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT_SECS));
+        let mut builder = Client::builder().timeout(timeout);
+
+        if let Some(ref ca_cert) = connection.ca_cert {
+            let pem = read_pem(ca_cert, "CA certificate")?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| certificate_error("CA certificate", ca_cert, e))?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        match (&connection.client_cert, &connection.client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert = read_pem(cert_path, "client certificate")?;
+                let key = read_pem(key_path, "client key")?;
+                let identity = reqwest::Identity::from_pkcs8_pem(&cert, &key)
+                    .map_err(|e| certificate_error("client certificate", cert_path, e))?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(HttpError::ClientBuildFailed(
+                    "client_cert and client_key must be set together".to_string(),
+                ));
+            }
+        }
+
+        if let Some(ref proxy_url) = connection.proxy {
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| HttpError::ClientBuildFailed(format!("invalid proxy: {}", e)))?
+                .no_proxy(
+                    connection
+                        .no_proxy
+                        .as_deref()
+                        .and_then(reqwest::NoProxy::from_string),
+                );
+            builder = builder.proxy(proxy);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| HttpError::ClientBuildFailed(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            retry,
+            cache: None,
+            not_before: Mutex::new(None),
+        })
+    }
+
+    /// Attach an on-disk response cache
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Send a GET request, retrying as described on `HttpClient`.
+    ///
+    /// The final response is returned even when it is not a success, so callers
+    /// keep full control over how status codes are reported. Only transport
+    /// errors that persist through every retry are returned as `HttpError`.
+    pub fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, HttpError> {
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(url));
+
+        let response = self.send_idempotent(url, || {
+            let mut request = self.client.get(url);
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            if let Some(ref entry) = cached {
+                if let Some(ref etag) = entry.etag {
+                    request = request.header("If-None-Match", etag);
+                }
+                if let Some(ref last_modified) = entry.last_modified {
+                    request = request.header("If-Modified-Since", last_modified);
+                }
+            }
+            request
+        })?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                return Ok(HttpResponse::from_cached(entry));
+            }
+        }
+
+        let response = HttpResponse::read(response).map_err(|e| HttpError::RequestFailed {
+            url: url.to_string(),
+            attempts: 1,
+            message: e.to_string(),
+        })?;
+
+        if response.status().is_success() {
+            if let Some(ref cache) = self.cache {
+                if let Some(entry) =
+                    CachedResponse::from_response(url, &response.headers, &response.body)
+                {
+                    cache.put(&entry);
+                }
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Send a POST request with a JSON body.
+    ///
+    /// POSTs create things, so they are sent once and never retried; only a
+    /// pending rate limit window is waited out first.
+    pub fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse, HttpError> {
+        self.wait_for_rate_limit(url)?;
+
+        let mut request = self.client.post(url).json(body);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let failed = |e: reqwest::Error| HttpError::RequestFailed {
+            url: url.to_string(),
+            attempts: 1,
+            message: e.to_string(),
+        };
+
+        let response = request.send().map_err(failed)?;
+        self.record_rate_limit(response.headers());
+
+        HttpResponse::read(response).map_err(failed)
+    }
+
+    fn send_idempotent<F>(&self, url: &str, build: F) -> Result<Response, HttpError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt: u32 = 0;
+
+        loop {
+            attempt += 1;
+            self.wait_for_rate_limit(url)?;
+
+            let retries_left = attempt <= self.retry.max_retries;
+
+            match build().send() {
+                Ok(response) => {
+                    self.record_rate_limit(response.headers());
+
+                    if !retries_left || !is_retryable_status(response.status()) {
+                        return Ok(response);
+                    }
+
+                    let delay = match server_delay(response.headers()) {
+                        Some(delay) => self.check_wait(url, delay)?,
+                        None => self.backoff(attempt),
+                    };
+                    std::thread::sleep(delay);
+                }
+                Err(e) => {
+                    if !retries_left || !is_retryable_error(&e) {
+                        return Err(HttpError::RequestFailed {
+                            url: url.to_string(),
+                            attempts: attempt,
+                            message: e.to_string(),
+                        });
+                    }
+
+                    std::thread::sleep(self.backoff(attempt));
+                }
+            }
+        }
+    }
+
+    /// Exponential backoff with "equal jitter": half the delay is fixed,
+    /// the other half is random, so concurrent clients spread out.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let base = self
+            .retry
+            .initial_backoff_ms
+            .saturating_mul(1u64 << exponent);
+        let capped = base.min(self.retry.max_backoff_ms);
+        let half = capped / 2;
+
+        Duration::from_millis(half + jitter(half))
+    }
+
+    /// A wait the server asked for, unless it is longer than allowed
+    fn check_wait(&self, url: &str, wait: Duration) -> Result<Duration, HttpError> {
+        if wait > Duration::from_millis(self.retry.max_backoff_ms) {
+            return Err(HttpError::WaitTooLong {
+                url: url.to_string(),
+                wait,
+                limit_ms: self.retry.max_backoff_ms,
+            });
+        }
+        Ok(wait)
+    }
+
+    fn wait_for_rate_limit(&self, url: &str) -> Result<(), HttpError> {
+        let not_before = self
+            .not_before
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take());
+
+        if let Some(instant) = not_before {
+            let now = Instant::now();
+            if instant > now {
+                std::thread::sleep(self.check_wait(url, instant - now)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        let remaining = header_u64(headers, "ratelimit-remaining");
+
+        if remaining == Some(0) {
+            if let Some(delay) = rate_limit_reset_delay(headers) {
+                if let Ok(mut guard) = self.not_before.lock() {
+                    *guard = Some(Instant::now() + delay);
+                }
+            }
+        }
+    }
+}
+
+fn read_pem(path: &std::path::Path, what: &'static str) -> Result<Vec<u8>, HttpError> {
+    std::fs::read(path).map_err(|e| certificate_error(what, path, e))
+}
+
+fn certificate_error(
+    what: &'static str,
+    path: &std::path::Path,
+    error: impl std::fmt::Display,
+) -> HttpError {
+    HttpError::CertificateError {
+        what,
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::INTERNAL_SERVER_ERROR
+        || status == StatusCode::BAD_GATEWAY
+        || status == StatusCode::SERVICE_UNAVAILABLE
+        || status == StatusCode::GATEWAY_TIMEOUT
+}
+
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// Delay requested by the server, from `Retry-After` or `RateLimit-Reset`
+fn server_delay(headers: &HeaderMap) -> Option<Duration> {
+    retry_after_delay(headers).or_else(|| rate_limit_reset_delay(headers))
+}
+
+/// Parse `Retry-After`, which is either delta-seconds or an HTTP date
+fn retry_after_delay(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("retry-after")?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parse `RateLimit-Reset`. GitLab sends a Unix timestamp, the IETF draft
+/// uses delta-seconds; anything that looks like a timestamp is treated as one.
+fn rate_limit_reset_delay(headers: &HeaderMap) -> Option<Duration> {
+    let value = header_u64(headers, "ratelimit-reset")?;
+
+    // 2001-09-09: no sane reset window is this many seconds away
+    const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+    if value >= EPOCH_THRESHOLD {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some(Duration::from_secs(value.saturating_sub(now)))
+    } else {
+        Some(Duration::from_secs(value))
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Random value in `0..=max`, good enough for spreading out retries
+fn jitter(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    hasher.finish() % (max + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_retry_after_seconds() {
+        let delay = server_delay(&headers(&[("retry-after", "7")]));
+        assert_eq!(delay, Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_retry_after_http_date_in_past_is_zero() {
+        let delay = server_delay(&headers(&[(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT",
+        )]));
+        assert_eq!(delay, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_rate_limit_reset_delta_seconds() {
+        let delay = server_delay(&headers(&[("ratelimit-reset", "12")]));
+        assert_eq!(delay, Some(Duration::from_secs(12)));
+    }
+
+    #[test]
+    fn test_missing_ca_cert_is_reported_with_path() {
+        let connection = ConnectionSettings {
+            ca_cert: Some("/definitely/missing/ca.pem".into()),
+            ..Default::default()
+        };
+
+        match HttpClient::new(RetryConfig::default(), &connection) {
+            Err(HttpError::CertificateError { path, .. }) => {
+                assert_eq!(path, "/definitely/missing/ca.pem");
+            }
+            _ => panic!("Expected CertificateError"),
+        }
+    }
+
+    #[test]
+    fn test_client_cert_requires_key() {
+        let connection = ConnectionSettings {
+            client_cert: Some("/tmp/cert.pem".into()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            HttpClient::new(RetryConfig::default(), &connection),
+            Err(HttpError::ClientBuildFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let client = HttpClient::new(
+            RetryConfig {
+                max_retries: 5,
+                initial_backoff_ms: 100,
+                max_backoff_ms: 300,
+            },
+            &ConnectionSettings::default(),
+        )
+        .unwrap();
+
+        let first = client.backoff(1);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+
+        let capped = client.backoff(10);
+        assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+    }
+}
@@ -1 +1,3 @@
+pub mod cache;
 pub mod gitlab;
+pub mod http;
@@ -0,0 +1,154 @@
+use assert_fs::TempDir;
+use git_ranger::commands::audit::{audit_command, AuditOptions};
+use git_ranger::commands::sync::{sync_command, SyncOptions};
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+
+use common::{commit_file, create_origin_repo, file_url, git};
+
+/// Workspace with `app` and `tool` freshly cloned
+fn synced_workspace(temp_dir: &TempDir) -> PathBuf {
+    let remote = temp_dir.path().join("remote");
+    let app = create_origin_repo(&remote, "app");
+    let tool = create_origin_repo(&remote, "tool");
+
+    let workspace = temp_dir.path().join("workspace");
+    fs::create_dir_all(&workspace).unwrap();
+    let config_path = workspace.join("ranger.yaml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+repos:
+  - url: "{}"
+  - url: "{}"
+"#,
+            file_url(&app),
+            file_url(&tool)
+        ),
+    )
+    .unwrap();
+
+    let report = sync_command(&SyncOptions {
+        config_path: config_path.clone(),
+        target: None,
+        dry_run: false,
+        refresh: false,
+        unshallow: false,
+    })
+    .unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    config_path
+}
+
+// Unit-style tests that call audit_command directly
+mod audit_unit_tests {
+    use super::*;
+
+    fn audit(config_path: &std::path::Path) -> git_ranger::commands::audit::AuditReport {
+        audit_command(&AuditOptions {
+            config_path: config_path.to_path_buf(),
+            target: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fresh_clones_have_nothing_unpushed() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+
+        let report = audit(&config_path);
+
+        assert_eq!(report.repos_checked, 2);
+        assert!(!report.has_findings());
+    }
+
+    #[test]
+    fn test_audit_finds_unpushed_branches_stashes_and_untracked_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let app = config_path.parent().unwrap().join("app");
+
+        git(&app, &["checkout", "-q", "-b", "experiment"]);
+        commit_file(&app, "notes.md", "one\n");
+        commit_file(&app, "notes.md", "two\n");
+        git(&app, &["checkout", "-q", "main"]);
+        fs::write(app.join("README.md"), "stashed edit\n").unwrap();
+        git(&app, &["stash"]);
+        fs::write(app.join("src/main.txt"), "uncommitted edit\n").unwrap();
+        fs::write(app.join("scratch.txt"), "untracked\n").unwrap();
+        fs::write(app.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(app.join("debug.log"), "ignored\n").unwrap();
+
+        let report = audit(&config_path);
+
+        assert_eq!(report.repos.len(), 1);
+        let found = &report.repos[0];
+        assert_eq!(found.name, "app");
+        assert_eq!(found.unpushed_branches.len(), 1);
+        assert_eq!(found.unpushed_branches[0].branch, "experiment");
+        assert_eq!(found.unpushed_branches[0].commits, 2);
+        assert_eq!(found.stashes.len(), 1);
+        assert_eq!(found.uncommitted_files, vec!["src/main.txt"]);
+        assert_eq!(found.untracked_files, vec![".gitignore", "scratch.txt"]);
+    }
+
+    #[test]
+    fn test_pushed_branches_are_not_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let tool = config_path.parent().unwrap().join("tool");
+
+        git(&tool, &["checkout", "-q", "-b", "feature"]);
+        commit_file(&tool, "feature.md", "done\n");
+        assert_eq!(
+            audit(&config_path).repos[0].unpushed_branches[0].branch,
+            "feature"
+        );
+
+        git(&tool, &["push", "-q", "origin", "feature"]);
+        assert!(!audit(&config_path).has_findings());
+    }
+}
+
+// Integration tests that run the CLI inside the workspace
+mod audit_integration_tests {
+    use super::*;
+    use std::process::Command;
+
+    fn get_binary_path() -> PathBuf {
+        let mut path = std::env::current_exe().unwrap();
+        path.pop();
+        if path.ends_with("deps") {
+            path.pop();
+        }
+        path.push(format!("git-ranger{}", std::env::consts::EXE_SUFFIX));
+        path
+    }
+
+    #[test]
+    fn test_audit_exits_non_zero_when_work_is_unpushed() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let workspace = config_path.parent().unwrap();
+
+        let run = || {
+            Command::new(get_binary_path())
+                .arg("audit")
+                .current_dir(workspace)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        assert!(run().status.success());
+
+        fs::write(workspace.join("tool/todo.txt"), "draft\n").unwrap();
+        let output = run();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Untracked: todo.txt"));
+    }
+}
@@ -0,0 +1,246 @@
+use assert_fs::TempDir;
+use git_ranger::commands::branch::{
+    branch_create_command, branch_list_command, checkout_command, BranchOptions, CheckoutOptions,
+};
+use git_ranger::commands::sync::{sync_command, SyncOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+
+use common::{create_origin_repo, file_url, git};
+
+/// Workspace with `app` and `tool` cloned; only `tool` has a `release` branch
+fn synced_workspace(temp_dir: &TempDir) -> PathBuf {
+    let remote = temp_dir.path().join("remote");
+    let app = create_origin_repo(&remote, "app");
+    let tool = create_origin_repo(&remote, "tool");
+    git(&tool, &["branch", "release"]);
+
+    let workspace = temp_dir.path().join("workspace");
+    fs::create_dir_all(&workspace).unwrap();
+    let config_path = workspace.join("ranger.yaml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+repos:
+  - url: "{}"
+  - url: "{}"
+"#,
+            file_url(&app),
+            file_url(&tool)
+        ),
+    )
+    .unwrap();
+
+    let report = sync_command(&SyncOptions {
+        config_path: config_path.clone(),
+        target: None,
+        dry_run: false,
+        refresh: false,
+        unshallow: false,
+    })
+    .unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    config_path
+}
+
+fn branch_options(config_path: &Path, target: Option<&str>, branch: &str) -> BranchOptions {
+    BranchOptions {
+        config_path: config_path.to_path_buf(),
+        target: target.map(String::from),
+        branch: branch.to_string(),
+    }
+}
+
+// Unit-style tests that call the branch commands directly
+mod branch_unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_is_created_from_default_branch_in_every_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let workspace = config_path.parent().unwrap();
+
+        let report =
+            branch_create_command(&branch_options(&config_path, None, "feature/x")).unwrap();
+
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.failures(), 0);
+        assert_eq!(report.results[0].message, "created from origin/main");
+        for name in ["app", "tool"] {
+            let clone = workspace.join(name);
+            assert_eq!(
+                git(&clone, &["symbolic-ref", "--short", "HEAD"]),
+                "feature/x"
+            );
+            assert_eq!(
+                git(&clone, &["rev-parse", "HEAD"]),
+                git(&clone, &["rev-parse", "origin/main"])
+            );
+        }
+
+        // A second attempt fails per repo instead of aborting
+        let report =
+            branch_create_command(&branch_options(&config_path, None, "feature/x")).unwrap();
+        assert_eq!(report.failures(), 2);
+        assert_eq!(
+            report.results[0].error.as_deref(),
+            Some("feature/x already exists")
+        );
+    }
+
+    #[test]
+    fn test_branch_list_shows_local_and_remote_presence() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+
+        let presence = branch_list_command(&branch_options(&config_path, None, "release")).unwrap();
+
+        assert_eq!(presence.len(), 2);
+        assert_eq!(
+            (
+                presence[0].name.as_str(),
+                presence[0].local,
+                presence[0].remote
+            ),
+            ("app", false, false)
+        );
+        assert_eq!(
+            (
+                presence[1].name.as_str(),
+                presence[1].local,
+                presence[1].remote
+            ),
+            ("tool", false, true)
+        );
+
+        let presence = branch_list_command(&branch_options(&config_path, None, "main")).unwrap();
+        assert!(presence
+            .iter()
+            .all(|repo| repo.local && repo.remote && repo.checked_out));
+    }
+
+    #[test]
+    fn test_checkout_uses_fallback_where_branch_is_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let workspace = config_path.parent().unwrap();
+        git(&workspace.join("app"), &["checkout", "-q", "-b", "scratch"]);
+
+        let report = checkout_command(&CheckoutOptions {
+            config_path: config_path.clone(),
+            target: None,
+            branch: "release".to_string(),
+            fallback: Some("main".to_string()),
+        })
+        .unwrap();
+
+        assert_eq!(report.failures(), 0);
+        assert_eq!(
+            report.results[0].message,
+            "release not found; checked out main"
+        );
+        assert_eq!(
+            report.results[1].message,
+            "checked out release tracking origin/release"
+        );
+        assert_eq!(
+            git(&workspace.join("app"), &["symbolic-ref", "--short", "HEAD"]),
+            "main"
+        );
+        assert_eq!(
+            git(
+                &workspace.join("tool"),
+                &["symbolic-ref", "--short", "HEAD"]
+            ),
+            "release"
+        );
+    }
+
+    #[test]
+    fn test_checkout_without_fallback_reports_missing_branch_and_honours_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+
+        let options = |target: Option<&str>| CheckoutOptions {
+            config_path: config_path.clone(),
+            target: target.map(String::from),
+            branch: "release".to_string(),
+            fallback: None,
+        };
+
+        let report = checkout_command(&options(None)).unwrap();
+        assert_eq!(report.failures(), 1);
+        assert_eq!(
+            report.results[0].error.as_deref(),
+            Some("release not found")
+        );
+
+        let report = checkout_command(&options(Some("tool"))).unwrap();
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].message, "already on release");
+    }
+
+    #[test]
+    fn test_repos_not_cloned_get_a_failure_result() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let missing = temp_dir.path().join("remote/missing");
+        let mut config = fs::read_to_string(&config_path).unwrap();
+        config.push_str(&format!("  - url: \"{}\"\n", file_url(&missing)));
+        fs::write(&config_path, config).unwrap();
+
+        let report =
+            branch_create_command(&branch_options(&config_path, None, "feature/y")).unwrap();
+
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.failures(), 1);
+        assert_eq!(report.results[2].error.as_deref(), Some("not cloned"));
+    }
+}
+
+// Integration tests that run the CLI inside the workspace
+mod branch_integration_tests {
+    use super::*;
+    use std::process::Command;
+
+    fn get_binary_path() -> PathBuf {
+        let mut path = std::env::current_exe().unwrap();
+        path.pop();
+        if path.ends_with("deps") {
+            path.pop();
+        }
+        path.push(format!("git-ranger{}", std::env::consts::EXE_SUFFIX));
+        path
+    }
+
+    #[test]
+    fn test_checkout_exits_non_zero_when_a_repo_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let workspace = config_path.parent().unwrap();
+
+        let run = |args: &[&str]| {
+            Command::new(get_binary_path())
+                .args(args)
+                .current_dir(workspace)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = run(&["checkout", "release"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("app: release not found"));
+
+        let output = run(&["checkout", "release", "--fallback", "main"]);
+        assert!(output.status.success());
+
+        let output = run(&["branch", "--list", "release"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Found in 1 of 2 repositories"));
+    }
+}
@@ -0,0 +1,170 @@
+use assert_fs::TempDir;
+use git_ranger::commands::branches::{
+    branches_command, BranchesOptions, BranchesReport, CleanupReason, DeleteMode,
+};
+use git_ranger::commands::sync::{sync_command, SyncOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+use common::{commit_file, create_origin_repo, file_url, git};
+
+/// Commit with author and committer dates in the past
+fn commit_dated(repo: &Path, path: &str, date: &str) {
+    fs::write(repo.join(path), date).unwrap();
+    git(repo, &["add", path]);
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(["commit", "-q", "-m", "Old work"])
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+/// A synced `app` clone with one branch of each kind:
+/// - `merged`: contained in origin/main
+/// - `gone-unpushed`: upstream deleted, its commit exists nowhere else
+/// - `old-pushed`: last commit in 2020, still on origin
+/// - `merged-checked-out`: merged, but checked out
+/// - `active`: recent and unmerged, pushed
+fn workspace_with_branches(temp_dir: &TempDir) -> (PathBuf, PathBuf) {
+    let origin = create_origin_repo(&temp_dir.path().join("remote"), "app");
+    let workspace = temp_dir.path().join("workspace");
+    fs::create_dir_all(&workspace).unwrap();
+    let config_path = workspace.join("ranger.yaml");
+    fs::write(
+        &config_path,
+        format!("repos:\n  - url: \"{}\"\n", file_url(&origin)),
+    )
+    .unwrap();
+
+    let report = sync_command(&SyncOptions {
+        config_path: config_path.clone(),
+        target: None,
+        dry_run: false,
+        refresh: false,
+        unshallow: false,
+    })
+    .unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    let clone = workspace.join("app");
+    git(&clone, &["branch", "merged", "main~1"]);
+
+    git(&clone, &["checkout", "-q", "-b", "gone-unpushed"]);
+    commit_file(&clone, "gone.md", "gone\n");
+    git(&clone, &["push", "-q", "-u", "origin", "gone-unpushed"]);
+    git(&origin, &["branch", "-D", "gone-unpushed"]);
+
+    git(&clone, &["checkout", "-q", "-b", "old-pushed", "main"]);
+    commit_dated(&clone, "old.md", "2020-01-01T12:00:00Z");
+    git(&clone, &["push", "-q", "origin", "old-pushed"]);
+
+    git(&clone, &["checkout", "-q", "-b", "active", "main"]);
+    commit_file(&clone, "active.md", "active\n");
+    git(&clone, &["push", "-q", "-u", "origin", "active"]);
+
+    git(
+        &clone,
+        &["checkout", "-q", "-b", "merged-checked-out", "main"],
+    );
+    git(&clone, &["fetch", "-q", "--prune"]);
+
+    (config_path, clone)
+}
+
+fn options(
+    config_path: &Path,
+    merged: bool,
+    stale_days: Option<u64>,
+    delete: DeleteMode,
+) -> BranchesOptions {
+    BranchesOptions {
+        config_path: config_path.to_path_buf(),
+        target: None,
+        merged,
+        stale_days,
+        delete,
+    }
+}
+
+fn found(report: &BranchesReport) -> Vec<(&str, Vec<CleanupReason>, Option<&str>)> {
+    report
+        .candidates
+        .iter()
+        .map(|c| (c.branch.as_str(), c.reasons.clone(), c.protected.as_deref()))
+        .collect()
+}
+
+// Unit-style tests that call branches_command directly
+mod branches_unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_and_gone_branches_are_found_and_protected_ones_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let (config_path, _) = workspace_with_branches(&temp_dir);
+
+        let report =
+            branches_command(&options(&config_path, true, None, DeleteMode::Report)).unwrap();
+
+        assert_eq!(
+            found(&report),
+            vec![
+                (
+                    "gone-unpushed",
+                    vec![CleanupReason::UpstreamGone],
+                    Some("1 unpushed commit")
+                ),
+                ("merged", vec![CleanupReason::Merged], None),
+                (
+                    "merged-checked-out",
+                    vec![CleanupReason::Merged],
+                    Some("checked out")
+                ),
+            ]
+        );
+        assert_eq!(report.deleted(), 0);
+    }
+
+    #[test]
+    fn test_stale_branches_are_found_by_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let (config_path, _) = workspace_with_branches(&temp_dir);
+
+        let report =
+            branches_command(&options(&config_path, false, Some(365), DeleteMode::Report)).unwrap();
+
+        assert_eq!(
+            found(&report),
+            vec![("old-pushed", vec![CleanupReason::Stale], None)]
+        );
+        assert!(report.candidates[0].age_days > 365);
+    }
+
+    #[test]
+    fn test_yes_deletes_only_unprotected_branches() {
+        let temp_dir = TempDir::new().unwrap();
+        let (config_path, clone) = workspace_with_branches(&temp_dir);
+
+        let report =
+            branches_command(&options(&config_path, true, Some(365), DeleteMode::Yes)).unwrap();
+
+        assert_eq!(report.deleted(), 2);
+        assert_eq!(report.errors(), 0);
+        let branches = git(
+            &clone,
+            &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+        );
+        assert_eq!(
+            branches.lines().collect::<Vec<_>>(),
+            vec!["active", "gone-unpushed", "main", "merged-checked-out"]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Helpers shared by the integration tests: local git repositories that
+//! stand in for provider-hosted ones.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Run git in `dir`, panicking with its stderr on failure
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args([
+            "-c",
+            "init.defaultBranch=main",
+            "-c",
+            "protocol.file.allow=always",
+        ])
+        .args(args)
+        .output()
+        .expect("Failed to run git");
+
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Write a file and commit it
+pub fn commit_file(repo: &Path, path: &str, content: &str) {
+    let file = repo.join(path);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(&file, content).unwrap();
+    git(repo, &["add", path]);
+    git(repo, &["commit", "-q", "-m", &format!("Update {}", path)]);
+}
+
+/// Create a repo at `dir/name` with a few commits on `main`
+pub fn create_origin_repo(dir: &Path, name: &str) -> PathBuf {
+    let repo = dir.join(name);
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q"]);
+    commit_file(&repo, "README.md", "# origin\n");
+    commit_file(&repo, "docs/guide.md", "guide\n");
+    commit_file(&repo, "src/main.txt", "main\n");
+    repo
+}
+
+/// `file://` URL for a local repo; unlike a plain path, this makes git use
+/// the same transport as for remote URLs (so `--depth` is honoured)
+pub fn file_url(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
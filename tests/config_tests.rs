@@ -123,3 +123,46 @@ providers:
     // Clean up
     unsafe { env::remove_var("GITLAB_TOKEN"); }
 }
+
+#[test]
+fn test_env_string_reads_token_from_file_source() {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Provider {
+        token: EnvString,
+    }
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let token_path = temp_dir.path().join("gitlab-token");
+    std::fs::write(&token_path, "file-token-123\n").unwrap();
+
+    let yaml = format!("token: \"file:{}\"", token_path.display());
+    let provider: Provider = serde_yaml::from_str(&yaml).expect("Should parse YAML");
+
+    assert_eq!(
+        provider.token.resolve_secret(None).unwrap(),
+        "file-token-123"
+    );
+}
+
+#[test]
+fn test_env_string_source_error_names_source_without_secret() {
+    let env_str = EnvString::new("cmd: echo super-secret-value; exit 1".to_string());
+    let error = env_str.resolve_secret(None).unwrap_err().to_string();
+
+    assert!(error.contains("cmd:echo"));
+    assert!(!error.contains("super-secret-value"));
+}
+
+#[test]
+fn test_resolved_secret_is_redacted_from_output() {
+    let env_str = EnvString::new("resolved-secret-for-redaction-test".to_string());
+    let secret = env_str.resolve_secret(None).unwrap();
+
+    let message = format!("remote rejected token {}", secret);
+    let redacted = git_ranger::redact::redact(&message);
+
+    assert!(!redacted.contains(&secret));
+    assert!(redacted.contains("[REDACTED]"));
+}
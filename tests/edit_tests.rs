@@ -0,0 +1,227 @@
+use assert_fs::TempDir;
+use git_ranger::commands::edit::{
+    add_command, group_add_command, remove_command, AddOptions, EditError, GroupAddOptions,
+    RemoveOptions,
+};
+use git_ranger::config::RangerConfig;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG: &str = r#"# Team workspace
+providers:
+  gitlab:
+    host: "https://gitlab.example.com"
+    token: ""  # set GITLAB_TOKEN instead
+
+groups:
+  gitlab:
+    - name: "platform"
+      recursive: true
+
+# Repos outside the group
+repos:
+  # the shared CI templates
+  - url: "git@gitlab.example.com:tools/ci.git"
+  - url: "https://github.com/org/app.git"
+    local_dir: "apps"  # keep apps together
+
+url_rewrites:
+  - from: "https://github.com/"
+    to: "git@github.com:"
+"#;
+
+fn workspace(temp_dir: &TempDir) -> PathBuf {
+    let config_path = temp_dir.path().join("ranger.yaml");
+    fs::write(&config_path, CONFIG).unwrap();
+    config_path
+}
+
+fn add(config_path: &Path, url: &str, dir: Option<&str>) -> Result<(), EditError> {
+    add_command(&AddOptions {
+        config_path: config_path.to_path_buf(),
+        url: url.to_string(),
+        dir: dir.map(String::from),
+    })
+}
+
+fn group_add(config_path: &Path, path: &str, recursive: bool) -> Result<(), EditError> {
+    group_add_command(&GroupAddOptions {
+        config_path: config_path.to_path_buf(),
+        provider: "gitlab".to_string(),
+        path: path.to_string(),
+        recursive,
+        dir: None,
+    })
+}
+
+// Unit-style tests that call the commands directly
+mod edit_unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_add_appends_to_repos_and_keeps_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = workspace(&temp_dir);
+
+        add(
+            &config_path,
+            "https://github.com/org/tool.git",
+            Some("tools"),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains(
+            "    local_dir: \"apps\"  # keep apps together\n  - url: \"https://github.com/org/tool.git\"\n    local_dir: \"tools\"\n\nurl_rewrites:\n"
+        ));
+        assert!(content.starts_with("# Team workspace\n"));
+        assert!(content.contains("token: \"\"  # set GITLAB_TOKEN instead\n"));
+        assert_eq!(
+            RangerConfig::load_from_file(&config_path)
+                .unwrap()
+                .repos
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_add_rejects_repos_sync_already_knows() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = workspace(&temp_dir);
+
+        // Same repo in another URL form
+        assert!(matches!(
+            add(&config_path, "https://GitLab.example.com/tools/CI", None),
+            Err(EditError::Duplicate(_))
+        ));
+        assert!(matches!(
+            add(&config_path, "git@github.com:org/app.git", None),
+            Err(EditError::Duplicate(_))
+        ));
+        // Inside the recursive group
+        assert!(matches!(
+            add(
+                &config_path,
+                "git@gitlab.example.com:platform/libs/core.git",
+                None
+            ),
+            Err(EditError::Duplicate(_))
+        ));
+        // Another repo would be cloned to the same directory
+        assert!(matches!(
+            add(&config_path, "git@github.com:fork/app.git", Some("apps/")),
+            Err(EditError::Duplicate(_))
+        ));
+
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), CONFIG);
+        add(&config_path, "git@github.com:fork/app.git", None).unwrap();
+    }
+
+    #[test]
+    fn test_remove_by_name_takes_the_entry_comment_along() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = workspace(&temp_dir);
+
+        remove_command(&RemoveOptions {
+            config_path: config_path.clone(),
+            repo: "ci".to_string(),
+        })
+        .unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains(
+            "# Repos outside the group\nrepos:\n  - url: \"https://github.com/org/app.git\"\n"
+        ));
+        assert!(!content.contains("CI templates"));
+
+        let result = remove_command(&RemoveOptions {
+            config_path: config_path.clone(),
+            repo: "ci".to_string(),
+        });
+        assert!(matches!(result, Err(EditError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_group_add_rejects_overlapping_groups() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = workspace(&temp_dir);
+
+        assert!(matches!(
+            group_add(&config_path, "platform/libs", false),
+            Err(EditError::Duplicate(_))
+        ));
+        assert!(matches!(
+            group_add(&config_path, "Platform", false),
+            Err(EditError::Duplicate(_))
+        ));
+
+        group_add(&config_path, "tools", false).unwrap();
+
+        let content = fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains("    - name: \"platform\"\n      recursive: true\n    - name: \"tools\"\n\n# Repos outside"));
+    }
+}
+
+// Integration tests that run the CLI inside the workspace
+mod edit_integration_tests {
+    use super::*;
+    use std::process::Command;
+
+    fn get_binary_path() -> PathBuf {
+        let mut path = std::env::current_exe().unwrap();
+        path.pop();
+        if path.ends_with("deps") {
+            path.pop();
+        }
+        path.push(format!("git-ranger{}", std::env::consts::EXE_SUFFIX));
+        path
+    }
+
+    #[test]
+    fn test_add_then_remove_restores_the_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = workspace(&temp_dir);
+
+        let run = |args: &[&str]| {
+            Command::new(get_binary_path())
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = run(&["add", "git@github.com:org/tool.git", "--dir", "tools"]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(
+            String::from_utf8_lossy(&output.stdout).contains("Added git@github.com:org/tool.git")
+        );
+
+        let output = run(&["remove", "https://github.com/org/tool"]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), CONFIG);
+    }
+
+    #[test]
+    fn test_group_add_with_unknown_provider_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        workspace(&temp_dir);
+
+        let output = Command::new(get_binary_path())
+            .args(["group", "add", "bitbucket", "team", "--recursive"])
+            .current_dir(temp_dir.path())
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown provider 'bitbucket'"));
+    }
+}
@@ -0,0 +1,178 @@
+use assert_fs::TempDir;
+use git_ranger::commands::grep::{grep_command, GrepOptions, OutputFormat};
+use git_ranger::commands::sync::{sync_command, SyncOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+
+use common::{commit_file, create_origin_repo, file_url, git};
+
+/// Workspace with `app` and `tool` cloned. `app` calls `legacyAuth()` in two
+/// files; `tool` only does so on its `release` branch.
+fn synced_workspace(temp_dir: &TempDir) -> PathBuf {
+    let remote = temp_dir.path().join("remote");
+    let app = create_origin_repo(&remote, "app");
+    commit_file(
+        &app,
+        "src/auth.rs",
+        "fn login() {\n    legacyAuth(user: &str);\n}\n",
+    );
+    commit_file(
+        &app,
+        "docs/migration.md",
+        "Replace legacyAuth() with sso()\n",
+    );
+    let tool = create_origin_repo(&remote, "tool");
+    git(&tool, &["checkout", "-q", "-b", "release"]);
+    commit_file(&tool, "lib/client.rs", "legacyAuth();\n");
+    git(&tool, &["checkout", "-q", "main"]);
+
+    let workspace = temp_dir.path().join("workspace");
+    fs::create_dir_all(&workspace).unwrap();
+    let config_path = workspace.join("ranger.yaml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+repos:
+  - url: "{}"
+  - url: "{}"
+"#,
+            file_url(&app),
+            file_url(&tool)
+        ),
+    )
+    .unwrap();
+
+    let report = sync_command(&SyncOptions {
+        config_path: config_path.clone(),
+        target: None,
+        dry_run: false,
+        refresh: false,
+        unshallow: false,
+    })
+    .unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    config_path
+}
+
+fn grep_options(config_path: &Path, pattern: &str) -> GrepOptions {
+    GrepOptions {
+        config_path: config_path.to_path_buf(),
+        target: None,
+        pattern: pattern.to_string(),
+        rev: None,
+        files_with_matches: false,
+        format: OutputFormat::Text,
+        jobs: None,
+    }
+}
+
+// Unit-style tests that call grep_command directly
+mod grep_unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_grep_groups_matches_by_repo_with_relative_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+
+        let report = grep_command(&grep_options(&config_path, "legacyAuth(")).unwrap();
+
+        assert_eq!(report.repos.len(), 1);
+        assert_eq!(report.repos[0].name, "app");
+        assert_eq!(report.total_matches(), 2);
+        let found = &report.repos[0].matches[1];
+        assert_eq!(found.path, "src/auth.rs");
+        assert_eq!(found.line, Some(2));
+        assert_eq!(found.text.as_deref(), Some("    legacyAuth(user: &str);"));
+    }
+
+    #[test]
+    fn test_grep_searches_a_ref_instead_of_the_working_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+
+        let report = grep_command(&GrepOptions {
+            rev: Some("origin/release".to_string()),
+            target: Some("tool".to_string()),
+            jobs: Some(1),
+            ..grep_options(&config_path, "legacyAuth")
+        })
+        .unwrap();
+
+        assert_eq!(report.repos.len(), 1);
+        assert_eq!(report.repos[0].name, "tool");
+        assert_eq!(report.repos[0].matches[0].path, "lib/client.rs");
+    }
+
+    #[test]
+    fn test_grep_files_with_matches_and_unknown_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+
+        let report = grep_command(&GrepOptions {
+            files_with_matches: true,
+            ..grep_options(&config_path, "legacyAuth")
+        })
+        .unwrap();
+        let paths: Vec<&str> = report.repos[0]
+            .matches
+            .iter()
+            .map(|m| m.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["docs/migration.md", "src/auth.rs"]);
+        assert!(report.repos[0].matches.iter().all(|m| m.line.is_none()));
+
+        let report = grep_command(&GrepOptions {
+            rev: Some("no-such-branch".to_string()),
+            ..grep_options(&config_path, "legacyAuth")
+        })
+        .unwrap();
+        assert_eq!(report.errors(), 2);
+        assert_eq!(report.total_matches(), 0);
+    }
+}
+
+// Integration tests that run the CLI inside the workspace
+mod grep_integration_tests {
+    use super::*;
+    use std::process::Command;
+
+    fn get_binary_path() -> PathBuf {
+        let mut path = std::env::current_exe().unwrap();
+        path.pop();
+        if path.ends_with("deps") {
+            path.pop();
+        }
+        path.push(format!("git-ranger{}", std::env::consts::EXE_SUFFIX));
+        path
+    }
+
+    #[test]
+    fn test_grep_json_output_and_exit_codes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = synced_workspace(&temp_dir);
+        let workspace = config_path.parent().unwrap();
+
+        let run = |args: &[&str]| {
+            Command::new(get_binary_path())
+                .args(args)
+                .current_dir(workspace)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = run(&["grep", "legacyAuth", "--format", "json"]);
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json[0]["name"], "app");
+        assert_eq!(json[0]["matches"][0]["path"], "docs/migration.md");
+        assert_eq!(json[0]["matches"][0]["line"], 1);
+
+        let output = run(&["grep", "doesNotOccurAnywhere"]);
+        assert_eq!(output.status.code(), Some(1));
+    }
+}
@@ -126,7 +126,7 @@ mod init_integration_tests {
         if path.ends_with("deps") {
             path.pop();
         }
-        path.push("git-ranger.exe");
+        path.push(format!("git-ranger{}", std::env::consts::EXE_SUFFIX));
         path
     }
 
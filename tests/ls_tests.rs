@@ -193,6 +193,80 @@ repos:
             path_str.contains("standalone") && path_str.contains("test-repo")
         }));
     }
+
+    #[test]
+    fn test_ls_interpolates_local_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("ranger.yaml");
+        let config_content = r#"
+repos:
+  - url: "https://github.com/example/test-repo.git"
+    local_dir: "${LS_TEST_TEAM_DIR:-team}/services"
+"#;
+        fs::write(&config_path, config_content).unwrap();
+
+        let options = LsOptions { config_path };
+
+        let repos = ls_command(&options).unwrap();
+
+        assert_eq!(
+            repos[0].local_path,
+            temp_dir.path().join("team/services").join("test-repo")
+        );
+    }
+
+    #[test]
+    fn test_ls_reports_config_key_of_unresolved_local_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("ranger.yaml");
+        let config_content = r#"
+repos:
+  - url: "https://github.com/example/test-repo.git"
+  - url: "https://github.com/example/other-repo.git"
+    local_dir: "${LS_TEST_DEFINITELY_UNSET_DIR}"
+"#;
+        fs::write(&config_path, config_content).unwrap();
+
+        let options = LsOptions { config_path };
+
+        match ls_command(&options) {
+            Err(LsError::ConfigValueError(e)) => {
+                assert!(e.to_string().contains("repos[1].local_dir"));
+            }
+            _ => panic!("Expected ConfigValueError"),
+        }
+    }
+
+    #[test]
+    fn test_ls_applies_url_rewrites() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("ranger.yaml");
+        let config_content = r#"
+url_rewrites:
+  - from: "https://github.com/"
+    to: "git@github.com:"
+  - from: "https://github.com/example/"
+    to: "https://mirror.example.com/example/"
+
+repos:
+  - url: "https://github.com/example/test-repo.git"
+  - url: "https://gitlab.example.com/team/other-repo.git"
+"#;
+        fs::write(&config_path, config_content).unwrap();
+
+        let options = LsOptions { config_path };
+
+        let repos = ls_command(&options).unwrap();
+
+        // Longest matching prefix wins
+        assert_eq!(repos[0].url, "https://github.com/example/test-repo.git");
+        assert_eq!(
+            repos[0].clone_url,
+            "https://mirror.example.com/example/test-repo.git"
+        );
+        // Unmatched URLs are left alone
+        assert_eq!(repos[1].clone_url, repos[1].url);
+    }
 }
 
 // Integration tests that simulate full workflow